@@ -9,49 +9,552 @@ wit_bindgen::generate!({
 
 pub use hyperware_app_common::SendResult;
 pub use hyperware_app_common::send;
+use hyperware_process_lib::http::client::send_request_await_response;
+use hyperware_process_lib::http::Method;
 use hyperware_process_lib::Address;
+use serde::de::DeserializeOwned;
 use serde_json::json;
+use std::collections::HashMap;
 
-// Import specific types from each interface
-pub use crate::wit_custom::TaskStatus;
-pub use crate::wit_custom::Task;
-pub use crate::wit_custom::TaskManagerStats;
-pub use crate::wit_custom::TaskStatusUpdateRequest;
-pub use crate::wit_custom::TaskResponse;
-pub use crate::wit_custom::NewTaskRequest;
-pub use crate::wit_custom::TaskStatus;
-pub use crate::wit_custom::Task;
-pub use crate::wit_custom::TaskManagerStats;
-pub use crate::wit_custom::TaskStatusUpdateRequest;
-pub use crate::wit_custom::TaskResponse;
-pub use crate::wit_custom::NewTaskRequest;
+/// Types shared with the Task Manager process.
+///
+/// ```
+/// use caller_utils::{
+///     ActivityEntry,
+///     AssigneeFormat,
+///     AssigneeStats,
+///     BulkCreateResponse,
+///     BulkDeleteResponse,
+///     BulkUpdateFailure,
+///     BulkUpdateResponse,
+///     CompletionMetrics,
+///     CursorPage,
+///     EffortRollup,
+///     HealthStatus,
+///     ImportConflict,
+///     MatchField,
+///     NewTaskRequest,
+///     PageRequest,
+///     ReconcileReport,
+///     RecurrenceRule,
+///     SearchResult,
+///     SortField,
+///     SortSpec,
+///     StorageEncodingReport,
+///     SyncReport,
+///     TagCount,
+///     Task,
+///     TaskBoard,
+///     TaskComment,
+///     TaskCounts,
+///     TaskError,
+///     TaskEvent,
+///     TaskEventKind,
+///     TaskFilter,
+///     TaskManagerStats,
+///     TaskPage,
+///     TaskPriority,
+///     TaskResponse,
+///     TaskStatus,
+///     TaskStatusUpdateRequest,
+///     TaskSummary,
+///     TaskTemplate,
+///     TaskUpdateRequest,
+///     TasksByIdsResponse,
+///     TasksSince,
+///     Tombstone,
+/// };
+/// ```
+pub use crate::wit_custom::{
+    ActivityEntry,
+    AssigneeFormat,
+    AssigneeStats,
+    BulkCreateResponse,
+    BulkDeleteResponse,
+    BulkUpdateFailure,
+    BulkUpdateResponse,
+    CompletionMetrics,
+    CursorPage,
+    EffortRollup,
+    HealthStatus,
+    ImportConflict,
+    MatchField,
+    NewTaskRequest,
+    PageRequest,
+    ReconcileReport,
+    RecurrenceRule,
+    SearchResult,
+    SortField,
+    SortSpec,
+    StorageEncodingReport,
+    SyncReport,
+    TagCount,
+    Task,
+    TaskBoard,
+    TaskComment,
+    TaskCounts,
+    TaskError,
+    TaskEvent,
+    TaskEventKind,
+    TaskFilter,
+    TaskManagerStats,
+    TaskPage,
+    TaskPriority,
+    TaskResponse,
+    TaskStatus,
+    TaskStatusUpdateRequest,
+    TaskSummary,
+    TaskTemplate,
+    TaskUpdateRequest,
+    TasksByIdsResponse,
+    TasksSince,
+    Tombstone,
+};
+
+/// Path of the Task Manager HTTP API, relative to the process base URL
+const TASK_MANAGER_API_PATH: &str = "/api/tasks";
+
+/// Timeout for HTTP RPC calls, in seconds
+const HTTP_RPC_TIMEOUT_SECS: u64 = 30;
+
+/// POST a JSON request to the Task Manager HTTP API and deserialize the reply.
+///
+/// `target` is the base URL of the Task Manager process, e.g.
+/// `http://localhost:8080/task-manager:task-manager:uncentered.os`.
+/// Non-2xx responses are returned as `SendResult::DeserializationError`
+/// carrying the status code and response body, and transport failures
+/// as `SendResult::Offline`.
+pub async fn send_http_rpc<R: DeserializeOwned>(request: &serde_json::Value, target: &str) -> SendResult<R> {
+    let url = format!("{}{}", target.trim_end_matches('/'), TASK_MANAGER_API_PATH);
+    let url = match url::Url::parse(&url) {
+        Ok(url) => url,
+        Err(e) => return SendResult::DeserializationError(format!("Invalid target URL {}: {}", url, e)),
+    };
+    let body = match serde_json::to_vec(request) {
+        Ok(body) => body,
+        Err(e) => return SendResult::DeserializationError(format!("Failed to serialize request: {}", e)),
+    };
+    let headers = HashMap::from([("Content-Type".to_string(), "application/json".to_string())]);
+
+    match send_request_await_response(Method::POST, url, Some(headers), HTTP_RPC_TIMEOUT_SECS, body) {
+        Ok(response) if !response.status().is_success() => SendResult::DeserializationError(format!(
+            "HTTP {}: {}",
+            response.status(),
+            String::from_utf8_lossy(response.body())
+        )),
+        Ok(response) => match serde_json::from_slice::<R>(response.body()) {
+            Ok(value) => SendResult::Success(value),
+            Err(e) => SendResult::DeserializationError(e.to_string()),
+        },
+        Err(_) => SendResult::Offline,
+    }
+}
 
 /// Generated RPC stubs for the task_manager interface
 pub mod task_manager {
     use crate::*;
 
     /// Generated stub for `create-task` http RPC call
-    pub async fn create_task_http_rpc(_target: &str, _new_task_req:  NewTaskRequest) -> SendResult<TaskResponse> {
-        // TODO: Implement HTTP endpoint
-        SendResult::Success(TaskResponse::default())
+    pub async fn create_task_http_rpc(target: &str, new_task_req: NewTaskRequest) -> SendResult<TaskResponse> {
+        let request = json!({"CreateTask": new_task_req});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `create-task-from-json` http RPC call. The reply is either a
+    /// `TaskResponse` or a `bad_request` body, so it is returned as plain JSON.
+    pub async fn create_task_from_json_http_rpc(target: &str, body: String) -> SendResult<serde_json::Value> {
+        let request = json!({"CreateTaskFromJson": body});
+        send_http_rpc::<serde_json::Value>(&request, target).await
+    }
+    
+    /// Generated stub for `save-template` http RPC call
+    pub async fn save_template_http_rpc(target: &str, name: String, template: TaskTemplate) -> SendResult<bool> {
+        let request = json!({"SaveTemplate": (name, template)});
+        send_http_rpc::<bool>(&request, target).await
+    }
+    
+    /// Generated stub for `list-templates` http RPC call
+    pub async fn list_templates_http_rpc(target: &str) -> SendResult<Vec<(String, TaskTemplate)>> {
+        let request = json!({"ListTemplates" : {}});
+        send_http_rpc::<Vec<(String, TaskTemplate)>>(&request, target).await
+    }
+    
+    /// Generated stub for `delete-template` http RPC call
+    pub async fn delete_template_http_rpc(target: &str, name: String) -> SendResult<bool> {
+        let request = json!({"DeleteTemplate": name});
+        send_http_rpc::<bool>(&request, target).await
+    }
+    
+    /// Generated stub for `create-from-template` http RPC call
+    pub async fn create_from_template_http_rpc(target: &str, template_name: String, overrides: NewTaskRequest, priority: Option<TaskPriority>) -> SendResult<TaskResponse> {
+        let request = json!({"CreateFromTemplate": (template_name, overrides, priority)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `duplicate-task` http RPC call
+    pub async fn duplicate_task_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"DuplicateTask": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `create-tasks-bulk` http RPC call
+    pub async fn create_tasks_bulk_http_rpc(target: &str, reqs: Vec<NewTaskRequest>) -> SendResult<BulkCreateResponse> {
+        let request = json!({"CreateTasksBulk": reqs});
+        send_http_rpc::<BulkCreateResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `import-tasks` http RPC call
+    pub async fn import_tasks_http_rpc(target: &str, tasks: Vec<Task>, on_conflict: ImportConflict) -> SendResult<BulkCreateResponse> {
+        let request = json!({"ImportTasks": (tasks, on_conflict)});
+        send_http_rpc::<BulkCreateResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `validate-tasks-bulk` http RPC call
+    pub async fn validate_tasks_bulk_http_rpc(target: &str, reqs: Vec<NewTaskRequest>) -> SendResult<BulkCreateResponse> {
+        let request = json!({"ValidateTasksBulk": reqs});
+        send_http_rpc::<BulkCreateResponse>(&request, target).await
     }
     
     /// Generated stub for `get-all-tasks` http RPC call
-    pub async fn get_all_tasks_http_rpc(_target: &str) -> SendResult<Vec<Task>> {
-        // TODO: Implement HTTP endpoint
-        SendResult::Success(Vec::new())
+    pub async fn get_all_tasks_http_rpc(target: &str) -> SendResult<Vec<Task>> {
+        let request = json!({"GetAllTasks" : {}});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `export-tasks-csv` http RPC call
+    pub async fn export_tasks_csv_http_rpc(target: &str) -> SendResult<String> {
+        let request = json!({"ExportTasksCsv" : {}});
+        send_http_rpc::<String>(&request, target).await
+    }
+    
+    /// Generated stub for `health` http RPC call
+    pub async fn health_http_rpc(target: &str) -> SendResult<HealthStatus> {
+        let request = json!({"Health" : {}});
+        send_http_rpc::<HealthStatus>(&request, target).await
+    }
+    
+    /// Generated stub for `metrics` http RPC call
+    pub async fn metrics_http_rpc(target: &str) -> SendResult<String> {
+        let request = json!({"Metrics" : {}});
+        send_http_rpc::<String>(&request, target).await
+    }
+    
+    /// Generated stub for `get-board` http RPC call
+    pub async fn get_board_http_rpc(target: &str) -> SendResult<TaskBoard> {
+        let request = json!({"GetBoard" : {}});
+        send_http_rpc::<TaskBoard>(&request, target).await
+    }
+    
+    /// Generated stub for `get-counts` http RPC call
+    pub async fn get_counts_http_rpc(target: &str) -> SendResult<TaskCounts> {
+        let request = json!({"GetCounts" : {}});
+        send_http_rpc::<TaskCounts>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tasks-paginated` http RPC call
+    pub async fn get_tasks_paginated_http_rpc(target: &str, req: PageRequest) -> SendResult<TaskPage> {
+        let request = json!({"GetTasksPaginated": req});
+        send_http_rpc::<TaskPage>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tasks-after` http RPC call
+    pub async fn get_tasks_after_http_rpc(target: &str, cursor: Option<String>, limit: u64) -> SendResult<CursorPage> {
+        let request = json!({"GetTasksAfter": (cursor, limit)});
+        send_http_rpc::<CursorPage>(&request, target).await
+    }
+    
+    /// Generated stub for `get-all-tasks-if-changed` http RPC call
+    pub async fn get_all_tasks_if_changed_http_rpc(target: &str, since_epoch: u64) -> SendResult<TasksSince> {
+        let request = json!({"GetAllTasksIfChanged": since_epoch});
+        send_http_rpc::<TasksSince>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tasks-changed-since` http RPC call
+    pub async fn get_tasks_changed_since_http_rpc(target: &str, since: u64) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksChangedSince": since});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tombstones-since` http RPC call
+    pub async fn get_tombstones_since_http_rpc(target: &str, since: u64) -> SendResult<Vec<Tombstone>> {
+        let request = json!({"GetTombstonesSince": since});
+        send_http_rpc::<Vec<Tombstone>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-recent-activity` http RPC call
+    pub async fn get_recent_activity_http_rpc(target: &str, limit: u64) -> SendResult<Vec<ActivityEntry>> {
+        let request = json!({"GetRecentActivity": limit});
+        send_http_rpc::<Vec<ActivityEntry>>(&request, target).await
+    }
+    
+    /// Generated stub for `list-task-summaries` http RPC call
+    pub async fn list_task_summaries_http_rpc(target: &str) -> SendResult<Vec<TaskSummary>> {
+        let request = json!({"ListTaskSummaries" : {}});
+        send_http_rpc::<Vec<TaskSummary>>(&request, target).await
+    }
+    
+    /// Generated stub for `touch-task` http RPC call
+    pub async fn touch_task_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"TouchTask": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `move-task-before` http RPC call
+    pub async fn move_task_before_http_rpc(target: &str, task_id: String, target_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"MoveTaskBefore": (task_id, target_id)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `move-task-to-top` http RPC call
+    pub async fn move_task_to_top_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"MoveTaskToTop": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `move-task-to-bottom` http RPC call
+    pub async fn move_task_to_bottom_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"MoveTaskToBottom": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `get-all-tasks-sorted` http RPC call
+    pub async fn get_all_tasks_sorted_http_rpc(target: &str, sort: SortSpec) -> SendResult<Vec<Task>> {
+        let request = json!({"GetAllTasksSorted": sort});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `search-tasks` http RPC call
+    pub async fn search_tasks_http_rpc(target: &str, query: String, include_comments: bool) -> SendResult<Vec<SearchResult>> {
+        let request = json!({"SearchTasks": (query, include_comments)});
+        send_http_rpc::<Vec<SearchResult>>(&request, target).await
+    }
+    
+    /// Generated stub for `query-tasks` http RPC call
+    pub async fn query_tasks_http_rpc(target: &str, filter: TaskFilter) -> SendResult<Vec<Task>> {
+        let request = json!({"QueryTasks": filter});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-overdue-tasks` http RPC call
+    pub async fn get_overdue_tasks_http_rpc(target: &str) -> SendResult<Vec<Task>> {
+        let request = json!({"GetOverdueTasks" : {}});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tasks-in-range` http RPC call
+    pub async fn get_tasks_in_range_http_rpc(target: &str, from: u64, to: u64) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksInRange": (from, to)});
+        send_http_rpc::<Vec<Task>>(&request, target).await
     }
     
     /// Generated stub for `get-task` http RPC call
-    pub async fn get_task_http_rpc(_target: &str, _task_id:  String) -> SendResult<TaskResponse> {
-        // TODO: Implement HTTP endpoint
-        SendResult::Success(TaskResponse::default())
+    pub async fn get_task_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"GetTask": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tasks-by-ids` http RPC call
+    pub async fn get_tasks_by_ids_http_rpc(target: &str, ids: Vec<String>) -> SendResult<TasksByIdsResponse> {
+        let request = json!({"GetTasksByIds": ids});
+        send_http_rpc::<TasksByIdsResponse>(&request, target).await
     }
     
     /// Generated stub for `update-task-status` http RPC call
-    pub async fn update_task_status_http_rpc(_target: &str, _update_req:  TaskStatusUpdateRequest) -> SendResult<TaskResponse> {
-        // TODO: Implement HTTP endpoint
-        SendResult::Success(TaskResponse::default())
+    pub async fn update_task_status_http_rpc(target: &str, update_req: TaskStatusUpdateRequest) -> SendResult<TaskResponse> {
+        let request = json!({"UpdateTaskStatus": update_req});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `update-statuses-bulk` http RPC call
+    pub async fn update_statuses_bulk_http_rpc(target: &str, updates: Vec<TaskStatusUpdateRequest>) -> SendResult<BulkUpdateResponse> {
+        let request = json!({"UpdateStatusesBulk": updates});
+        send_http_rpc::<BulkUpdateResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `cancel-task` http RPC call
+    pub async fn cancel_task_http_rpc(target: &str, task_id: String, reason: Option<String>) -> SendResult<TaskResponse> {
+        let request = json!({"CancelTask": (task_id, reason)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `update-task` http RPC call
+    pub async fn update_task_http_rpc(target: &str, req: TaskUpdateRequest) -> SendResult<TaskResponse> {
+        let request = json!({"UpdateTask": req});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `patch-task` http RPC call
+    pub async fn patch_task_http_rpc(target: &str, task_id: String, patch: serde_json::Value, strict: bool) -> SendResult<TaskResponse> {
+        let request = json!({"PatchTask": (task_id, patch, strict)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `reassign-task` http RPC call
+    pub async fn reassign_task_http_rpc(target: &str, task_id: String, assignee: Option<String>) -> SendResult<TaskResponse> {
+        let request = json!({"ReassignTask": (task_id, assignee)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `reassign-all` http RPC call
+    pub async fn reassign_all_http_rpc(target: &str, from: String, to: Option<String>) -> SendResult<BulkUpdateResponse> {
+        let request = json!({"ReassignAll": (from, to)});
+        send_http_rpc::<BulkUpdateResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `add-assignee` http RPC call
+    pub async fn add_assignee_http_rpc(target: &str, task_id: String, assignee: String) -> SendResult<TaskResponse> {
+        let request = json!({"AddAssignee": (task_id, assignee)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `remove-assignee` http RPC call
+    pub async fn remove_assignee_http_rpc(target: &str, task_id: String, assignee: String) -> SendResult<TaskResponse> {
+        let request = json!({"RemoveAssignee": (task_id, assignee)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `get-subtasks` http RPC call
+    pub async fn get_subtasks_http_rpc(target: &str, parent_id: String) -> SendResult<Vec<Task>> {
+        let request = json!({"GetSubtasks": parent_id});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-task-history` http RPC call
+    pub async fn get_task_history_http_rpc(target: &str, task_id: String) -> SendResult<Vec<TaskEvent>> {
+        let request = json!({"GetTaskHistory": task_id});
+        send_http_rpc::<Vec<TaskEvent>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tasks-by-tag` http RPC call
+    pub async fn get_tasks_by_tag_http_rpc(target: &str, tag: String) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksByTag": tag});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-tag-cloud` http RPC call
+    pub async fn get_tag_cloud_http_rpc(target: &str) -> SendResult<Vec<TagCount>> {
+        let request = json!({"GetTagCloud" : {}});
+        send_http_rpc::<Vec<TagCount>>(&request, target).await
+    }
+    
+    /// Generated stub for `add-tag` http RPC call
+    pub async fn add_tag_http_rpc(target: &str, task_id: String, tag: String) -> SendResult<TaskResponse> {
+        let request = json!({"AddTag": (task_id, tag)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `remove-tag` http RPC call
+    pub async fn remove_tag_http_rpc(target: &str, task_id: String, tag: String) -> SendResult<TaskResponse> {
+        let request = json!({"RemoveTag": (task_id, tag)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `add-dependency` http RPC call
+    pub async fn add_dependency_http_rpc(target: &str, task_id: String, blocker_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"AddDependency": (task_id, blocker_id)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `remove-dependency` http RPC call
+    pub async fn remove_dependency_http_rpc(target: &str, task_id: String, blocker_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"RemoveDependency": (task_id, blocker_id)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `get-blocked-tasks` http RPC call
+    pub async fn get_blocked_tasks_http_rpc(target: &str) -> SendResult<Vec<Task>> {
+        let request = json!({"GetBlockedTasks" : {}});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `set-metadata` http RPC call
+    pub async fn set_metadata_http_rpc(target: &str, task_id: String, key: String, value: String) -> SendResult<TaskResponse> {
+        let request = json!({"SetMetadata": (task_id, key, value)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `remove-metadata` http RPC call
+    pub async fn remove_metadata_http_rpc(target: &str, task_id: String, key: String) -> SendResult<TaskResponse> {
+        let request = json!({"RemoveMetadata": (task_id, key)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `delete-task` http RPC call
+    pub async fn delete_task_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"DeleteTask": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `clear-completed` http RPC call
+    pub async fn clear_completed_http_rpc(target: &str) -> SendResult<BulkDeleteResponse> {
+        let request = json!({"ClearCompleted" : {}});
+        send_http_rpc::<BulkDeleteResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `add-comment` http RPC call
+    pub async fn add_comment_http_rpc(target: &str, task_id: String, author: String, body: String) -> SendResult<TaskResponse> {
+        let request = json!({"AddComment": (task_id, author, body)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `log-work` http RPC call
+    pub async fn log_work_http_rpc(target: &str, task_id: String, secs: u64) -> SendResult<TaskResponse> {
+        let request = json!({"LogWork": (task_id, secs)});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `get-comments` http RPC call
+    pub async fn get_comments_http_rpc(target: &str, task_id: String) -> SendResult<Vec<TaskComment>> {
+        let request = json!({"GetComments": task_id});
+        send_http_rpc::<Vec<TaskComment>>(&request, target).await
+    }
+    
+    /// Generated stub for `archive-task` http RPC call
+    pub async fn archive_task_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"ArchiveTask": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `unarchive-task` http RPC call
+    pub async fn unarchive_task_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"UnarchiveTask": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `restore-task` http RPC call
+    pub async fn restore_task_http_rpc(target: &str, task_id: String) -> SendResult<TaskResponse> {
+        let request = json!({"RestoreTask": task_id});
+        send_http_rpc::<TaskResponse>(&request, target).await
+    }
+    
+    /// Generated stub for `get-archived-tasks` http RPC call
+    pub async fn get_archived_tasks_http_rpc(target: &str) -> SendResult<Vec<Task>> {
+        let request = json!({"GetArchivedTasks" : {}});
+        send_http_rpc::<Vec<Task>>(&request, target).await
+    }
+    
+    /// Generated stub for `get-assignee-workload` http RPC call
+    pub async fn get_assignee_workload_http_rpc(target: &str) -> SendResult<Vec<(String, AssigneeStats)>> {
+        let request = json!({"GetAssigneeWorkload" : {}});
+        send_http_rpc::<Vec<(String, AssigneeStats)>>(&request, target).await
+    }
+    
+    /// Generated stub for `create-task` remote RPC call
+    pub async fn create_task_remote_rpc(target: &Address, new_task_req: NewTaskRequest) -> SendResult<TaskResponse> {
+        let request = json!({"CreateTask": new_task_req});
+        send::<TaskResponse>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `create-task` local RPC call
+    pub async fn create_task_local_rpc(target: &Address, new_task_req: NewTaskRequest) -> SendResult<TaskResponse> {
+        let request = json!({"CreateTask": new_task_req});
+        send::<TaskResponse>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `update-task-status` local RPC call
+    pub async fn update_task_status_local_rpc(target: &Address, update_req: TaskStatusUpdateRequest) -> SendResult<TaskResponse> {
+        let request = json!({"UpdateTaskStatus": update_req});
+        send::<TaskResponse>(&request, target, 30).await
     }
     
     /// Generated stub for `get-statistics` local RPC call
@@ -60,6 +563,180 @@ pub mod task_manager {
         send::<TaskManagerStats>(&request, target, 30).await
     }
     
+    /// Generated stub for `get-assignee-workload` local RPC call
+    pub async fn get_assignee_workload_local_rpc(target: &Address) -> SendResult<Vec<(String, AssigneeStats)>> {
+        let request = json!({"GetAssigneeWorkload" : {}});
+        send::<Vec<(String, AssigneeStats)>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-effort-rollup` local RPC call
+    pub async fn get_effort_rollup_local_rpc(target: &Address) -> SendResult<EffortRollup> {
+        let request = json!({"GetEffortRollup" : {}});
+        send::<EffortRollup>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-completion-metrics` local RPC call
+    pub async fn get_completion_metrics_local_rpc(target: &Address, since: u64) -> SendResult<CompletionMetrics> {
+        let request = json!({"GetCompletionMetrics": since});
+        send::<CompletionMetrics>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `reset-statistics` local RPC call
+    pub async fn reset_statistics_local_rpc(target: &Address) -> SendResult<()> {
+        let request = json!({"ResetStatistics" : {}});
+        send::<()>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-seed-welcome-task` local RPC call
+    pub async fn set_seed_welcome_task_local_rpc(target: &Address, enabled: bool) -> SendResult<()> {
+        let request = json!({"SetSeedWelcomeTask": enabled});
+        send::<()>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-last-storage-correlation-id` local RPC call
+    pub async fn get_last_storage_correlation_id_local_rpc(target: &Address) -> SendResult<Option<String>> {
+        let request = json!({"GetLastStorageCorrelationId" : {}});
+        send::<Option<String>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-subscribe-sequence` local RPC call
+    pub async fn get_subscribe_sequence_local_rpc(target: &Address) -> SendResult<Vec<String>> {
+        let request = json!({"GetSubscribeSequence" : {}});
+        send::<Vec<String>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-resume-frames` local RPC call
+    pub async fn get_resume_frames_local_rpc(target: &Address, run_id: Option<String>, since_seq: Option<u64>) -> SendResult<Vec<String>> {
+        let request = json!({"GetResumeFrames": (run_id, since_seq)});
+        send::<Vec<String>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-storage-attempts` local RPC call
+    pub async fn set_storage_attempts_local_rpc(target: &Address, attempts: u32) -> SendResult<bool> {
+        let request = json!({"SetStorageAttempts": attempts});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-compact-storage` local RPC call
+    pub async fn set_compact_storage_local_rpc(target: &Address, enabled: bool) -> SendResult<bool> {
+        let request = json!({"SetCompactStorage": enabled});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `compare-storage-encodings` local RPC call
+    pub async fn compare_storage_encodings_local_rpc(target: &Address) -> SendResult<StorageEncodingReport> {
+        let request = json!({"CompareStorageEncodings" : {}});
+        send::<StorageEncodingReport>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-admins` local RPC call
+    pub async fn set_admins_local_rpc(target: &Address, admins: Vec<String>) -> SendResult<()> {
+        let request = json!({"SetAdmins": admins});
+        send::<()>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-assignee-format` local RPC call
+    pub async fn set_assignee_format_local_rpc(target: &Address, format: AssigneeFormat) -> SendResult<()> {
+        let request = json!({"SetAssigneeFormat": format});
+        send::<()>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-completed-retention-secs` local RPC call
+    pub async fn set_completed_retention_secs_local_rpc(target: &Address, retention_secs: Option<u64>) -> SendResult<bool> {
+        let request = json!({"SetCompletedRetentionSecs": retention_secs});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `run-auto-archive` local RPC call
+    pub async fn run_auto_archive_local_rpc(target: &Address) -> SendResult<Vec<String>> {
+        let request = json!({"RunAutoArchive" : {}});
+        send::<Vec<String>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-max-tasks` local RPC call
+    pub async fn set_max_tasks_local_rpc(target: &Address, max_tasks: Option<u64>) -> SendResult<bool> {
+        let request = json!({"SetMaxTasks": max_tasks});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `subscribe-test-channel` local RPC call
+    pub async fn subscribe_test_channel_local_rpc(target: &Address, channel_id: u32) -> SendResult<Vec<String>> {
+        let request = json!({"SubscribeTestChannel": channel_id});
+        send::<Vec<String>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-snapshot-frames` local RPC call
+    pub async fn get_snapshot_frames_local_rpc(target: &Address, compression: Option<String>) -> SendResult<Vec<String>> {
+        let request = json!({"GetSnapshotFrames": compression});
+        send::<Vec<String>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `disconnect-ws-channel` local RPC call
+    pub async fn disconnect_ws_channel_local_rpc(target: &Address, channel_id: u32) -> SendResult<bool> {
+        let request = json!({"DisconnectWsChannel": channel_id});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-max-ws-connections` local RPC call
+    pub async fn set_max_ws_connections_local_rpc(target: &Address, max_ws_connections: u64) -> SendResult<bool> {
+        let request = json!({"SetMaxWsConnections": max_ws_connections});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-ws-token` local RPC call
+    pub async fn set_ws_token_local_rpc(target: &Address, token: Option<String>) -> SendResult<bool> {
+        let request = json!({"SetWsToken": token});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `sync-pending-storage` local RPC call
+    pub async fn sync_pending_storage_local_rpc(target: &Address) -> SendResult<Vec<String>> {
+        let request = json!({"SyncPendingStorage" : {}});
+        send::<Vec<String>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `sync-all-to-storage` local RPC call
+    pub async fn sync_all_to_storage_local_rpc(target: &Address) -> SendResult<SyncReport> {
+        let request = json!({"SyncAllToStorage" : {}});
+        send::<SyncReport>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `reconcile-with-storage` local RPC call
+    pub async fn reconcile_with_storage_local_rpc(target: &Address) -> SendResult<ReconcileReport> {
+        let request = json!({"ReconcileWithStorage" : {}});
+        send::<ReconcileReport>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-storage-address` local RPC call
+    pub async fn get_storage_address_local_rpc(target: &Address) -> SendResult<String> {
+        let request = json!({"GetStorageAddress" : {}});
+        send::<String>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `set-storage-address` local RPC call
+    pub async fn set_storage_address_local_rpc(target: &Address, addr: String) -> SendResult<bool> {
+        let request = json!({"SetStorageAddress": addr});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `query-tasks` remote RPC call
+    pub async fn query_tasks_remote_rpc(target: &Address, filter: TaskFilter) -> SendResult<Vec<Task>> {
+        let request = json!({"QueryTasks": filter});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-tasks-in-range` remote RPC call
+    pub async fn get_tasks_in_range_remote_rpc(target: &Address, from: u64, to: u64) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksInRange": (from, to)});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `check-status-index` local RPC call
+    pub async fn check_status_index_local_rpc(target: &Address) -> SendResult<bool> {
+        let request = json!({"CheckStatusIndex" : {}});
+        send::<bool>(&request, target, 30).await
+    }
+    
     /// Generated stub for `get-tasks-by-status` remote RPC call
     pub async fn get_tasks_by_status_remote_rpc(target: &Address, status: TaskStatus) -> SendResult<Vec<Task>> {
         let request = json!({"GetTasksByStatus": status});
@@ -72,6 +749,133 @@ pub mod task_manager {
         send::<Vec<Task>>(&request, target, 30).await
     }
     
+    /// Generated stub for `get-tasks-by-priority` remote RPC call
+    pub async fn get_tasks_by_priority_remote_rpc(target: &Address, priority: TaskPriority) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksByPriority": priority});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-tasks-by-priority` local RPC call
+    pub async fn get_tasks_by_priority_local_rpc(target: &Address, priority: TaskPriority) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksByPriority": priority});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-tasks-by-assignee` remote RPC call
+    pub async fn get_tasks_by_assignee_remote_rpc(target: &Address, assignee: String) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksByAssignee": assignee});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-tasks-by-assignee` local RPC call
+    pub async fn get_tasks_by_assignee_local_rpc(target: &Address, assignee: String) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksByAssignee": assignee});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-unassigned-tasks` remote RPC call
+    pub async fn get_unassigned_tasks_remote_rpc(target: &Address) -> SendResult<Vec<Task>> {
+        let request = json!({"GetUnassignedTasks" : {}});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-unassigned-tasks` local RPC call
+    pub async fn get_unassigned_tasks_local_rpc(target: &Address) -> SendResult<Vec<Task>> {
+        let request = json!({"GetUnassignedTasks" : {}});
+        send::<Vec<Task>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `flush-state` local RPC call
+    pub async fn flush_state_local_rpc(target: &Address) -> SendResult<bool> {
+        let request = json!({"FlushState" : {}});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `get-saved-task-ids` local RPC call
+    pub async fn get_saved_task_ids_local_rpc(target: &Address) -> SendResult<Option<Vec<String>>> {
+        let request = json!({"GetSavedTaskIds" : {}});
+        send::<Option<Vec<String>>>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `prepare-shutdown` local RPC call
+    pub async fn prepare_shutdown_local_rpc(target: &Address) -> SendResult<bool> {
+        let request = json!({"PrepareShutdown" : {}});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `notify-assignment` remote RPC call
+    pub async fn notify_assignment_remote_rpc(target: &Address, task: Task) -> SendResult<bool> {
+        let request = json!({"NotifyAssignment": task});
+        send::<bool>(&request, target, 30).await
+    }
+    
+    /// Generated stub for `on-external-task-change` remote RPC call
+    pub async fn on_external_task_change_remote_rpc(target: &Address, task: Task) -> SendResult<bool> {
+        let request = json!({"OnExternalTaskChange": task});
+        send::<bool>(&request, target, 30).await
+    }
+    
     
 }
 
+/// RPC stubs for the task-storage process the Task Manager persists into
+pub mod task_storage {
+    use crate::*;
+
+    /// Stub for the storage `add-task` remote RPC call
+    pub async fn add_task_remote_rpc(target: &Address, task: Task, timeout: u64) -> SendResult<bool> {
+        let request = json!({"AddTask": task});
+        send::<bool>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `add-tasks` remote RPC call, storing a batch of tasks at once
+    pub async fn add_tasks_remote_rpc(target: &Address, tasks: Vec<Task>, timeout: u64) -> SendResult<bool> {
+        let request = json!({"AddTasks": tasks});
+        send::<bool>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `add-task-packed` remote RPC call. `task` is packed as MessagePack
+    /// (structs as arrays) and base64 encoded.
+    pub async fn add_task_packed_remote_rpc(target: &Address, task: String, timeout: u64) -> SendResult<bool> {
+        let request = json!({"AddTaskPacked": task});
+        send::<bool>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `add-tasks-packed` remote RPC call, storing a packed batch of tasks
+    pub async fn add_tasks_packed_remote_rpc(target: &Address, tasks: String, timeout: u64) -> SendResult<bool> {
+        let request = json!({"AddTasksPacked": tasks});
+        send::<bool>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `get-tasks-by-status` remote RPC call
+    pub async fn get_tasks_by_status_remote_rpc(target: &Address, status: TaskStatus, timeout: u64) -> SendResult<Vec<Task>> {
+        let request = json!({"GetTasksByStatus": status});
+        send::<Vec<Task>>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `get-all-tasks` remote RPC call
+    pub async fn get_all_tasks_remote_rpc(target: &Address, timeout: u64) -> SendResult<Vec<Task>> {
+        let request = json!({"GetAllTasks" : {}});
+        send::<Vec<Task>>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `get-all-tasks-packed` remote RPC call, returning the tasks packed
+    /// like `add-tasks-packed` takes them
+    pub async fn get_all_tasks_packed_remote_rpc(target: &Address, timeout: u64) -> SendResult<String> {
+        let request = json!({"GetAllTasksPacked" : {}});
+        send::<String>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `get-capabilities` remote RPC call, listing optional features such
+    /// as `msgpack` for the packed calls
+    pub async fn get_capabilities_remote_rpc(target: &Address, timeout: u64) -> SendResult<Vec<String>> {
+        let request = json!({"GetCapabilities" : {}});
+        send::<Vec<String>>(&request, target, timeout).await
+    }
+    
+    /// Stub for the storage `delete-task` remote RPC call
+    pub async fn delete_task_remote_rpc(target: &Address, task_id: String, timeout: u64) -> SendResult<bool> {
+        let request = json!({"DeleteTask": task_id});
+        send::<bool>(&request, target, timeout).await
+    }
+}
@@ -1,15 +1,27 @@
-use hyperware_app_common::{Binding, SaveOptions, SendResult};
+use hyperware_app_common::{send, Binding, SaveOptions, SendResult};
 use hyperware_process_lib::http::server::{HttpBindingConfig, WsBindingConfig, WsMessageType};
-use hyperware_process_lib::{Address, LazyLoadBlob};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use hyperware_process_lib::timer::TimerAction;
+use hyperware_process_lib::{Address, LazyLoadBlob, Request};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::io::Write;
 use uuid::Uuid;
 
 // Import caller utilities after running hyper-bindgen
-use caller_utils::task_storage::{add_task_remote_rpc, get_tasks_by_status_remote_rpc};
+use caller_utils::task_manager::notify_assignment_remote_rpc;
+use caller_utils::task_storage::{
+    add_task_packed_remote_rpc, add_task_remote_rpc, add_tasks_packed_remote_rpc, add_tasks_remote_rpc,
+    delete_task_remote_rpc, get_all_tasks_packed_remote_rpc, get_all_tasks_remote_rpc,
+    get_capabilities_remote_rpc, get_tasks_by_status_remote_rpc,
+};
 
 // Define task-related types
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// Ordered by workflow stage, in declaration order
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TaskStatus {
     Pending,
     InProgress,
@@ -17,30 +29,528 @@ pub enum TaskStatus {
     Cancelled,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Allowed status changes as (from, to) pairs. Completed and Cancelled are terminal.
+const STATUS_TRANSITIONS: &[(TaskStatus, TaskStatus)] = &[
+    (TaskStatus::Pending, TaskStatus::InProgress),
+    (TaskStatus::Pending, TaskStatus::Cancelled),
+    (TaskStatus::InProgress, TaskStatus::Completed),
+    (TaskStatus::InProgress, TaskStatus::Cancelled),
+];
+
+impl TaskStatus {
+    /// Whether a task in this status may move to `next`. Keeping the same status is always allowed.
+    pub fn can_transition_to(&self, next: &TaskStatus) -> bool {
+        self == next
+            || STATUS_TRANSITIONS
+                .iter()
+                .any(|(from, to)| from == self && to == next)
+    }
+}
+
+// Ordered from least to most urgent, in declaration order
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     id: String,
     title: String,
     description: String,
     status: TaskStatus,
     created_at: u64,
-    assigned_to: Option<String>,
+    // Normalized (trimmed), de-duplicated assignees; older state stored a single `assigned_to`
+    #[serde(default, alias = "assigned_to", deserialize_with = "deserialize_assignees")]
+    assignees: Vec<String>,
+    #[serde(default)]
+    priority: TaskPriority,
+    // Deadline as unix seconds
+    #[serde(default)]
+    due_at: Option<u64>,
+    // Normalized (trimmed, lowercase), de-duplicated labels
+    #[serde(default)]
+    tags: Vec<String>,
+    // Audit trail of changes, oldest first, capped at MAX_HISTORY_EVENTS
+    #[serde(default)]
+    history: Vec<TaskEvent>,
+    // Archived tasks are kept for audit but hidden from default listings
+    #[serde(default)]
+    archived: bool,
+    // Progress notes, oldest first
+    #[serde(default)]
+    comments: Vec<TaskComment>,
+    // Parent task when this is a subtask
+    #[serde(default)]
+    parent_id: Option<String>,
+    // Bumped on every change so clients can detect concurrent edits
+    #[serde(default)]
+    version: u64,
+    // When set, completing the task creates its next occurrence
+    #[serde(default)]
+    recurrence: Option<RecurrenceRule>,
+    // Free-form custom fields, capped by MAX_METADATA_KEYS and the key/value length limits.
+    // Sent as a list of pairs, like the WIT `list<tuple<string, string>>`.
+    #[serde(default, serialize_with = "serialize_pairs", deserialize_with = "deserialize_pairs")]
+    metadata: HashMap<String, String>,
+    // Unix seconds of the last change or touch. Tasks stored without it load as 0 and are
+    // backfilled with `created_at` (see `backfill_defaults`).
+    #[serde(default)]
+    last_activity_at: u64,
+    // Unix seconds the task became Completed; None for open tasks and ones completed before this
+    // was tracked (see `completion_time`)
+    #[serde(default)]
+    completed_at: Option<u64>,
+    // Position in the manual order, lowest first. Moves pick a value between the neighbours, so
+    // only the moved task changes. Tasks stored without it are backfilled with `created_at`.
+    #[serde(default = "unset_sort_order")]
+    sort_order: f64,
+    // Ids of tasks that must be Completed before this one can start; deleted ones no longer block
+    #[serde(default)]
+    blocked_by: Vec<String>,
+    // Identity of whoever created the task (see `caller_identity`); only they and admins may
+    // change it. None for tasks created anonymously over HTTP or from before ownership was
+    // tracked, which anyone may change.
+    #[serde(default)]
+    owner: Option<String>,
+    // Expected effort, in seconds; None when nobody has estimated the task
+    #[serde(default)]
+    estimate_secs: Option<u64>,
+    // Effort recorded through `log_work` so far, in seconds
+    #[serde(default)]
+    logged_secs: u64,
+}
+
+// Placeholder for a missing `sort_order`, replaced by `backfill_defaults`
+fn unset_sort_order() -> f64 {
+    f64::NAN
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecurrenceRule {
+    // Time from an occurrence's creation to its due date, in seconds
+    interval_secs: u64,
+}
+
+/// Maximum comment body length, in characters
+const MAX_COMMENT_LEN: usize = 4096;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskComment {
+    id: String,
+    author: String,
+    body: String,
+    at: u64,
+}
+
+/// Maximum number of history events kept per task; the oldest are dropped first
+const MAX_HISTORY_EVENTS: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskEvent {
+    at: u64,
+    kind: TaskEventKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TaskEventKind {
+    Created,
+    StatusChanged { from: TaskStatus, to: TaskStatus },
+    // Older history recorded single optional assignees; both forms load as lists
+    Reassigned {
+        #[serde(deserialize_with = "deserialize_assignees")]
+        from: Vec<String>,
+        #[serde(deserialize_with = "deserialize_assignees")]
+        to: Vec<String>,
+    },
+    Archived,
+    Unarchived,
+    // Cancelled through `cancel_task`, which records this instead of a StatusChanged event
+    Cancelled { reason: Option<String> },
+    // Brought back from Cancelled to Pending through `restore_task`
+    Restored,
+    // Effort recorded through `log_work`
+    WorkLogged { secs: u64 },
+}
+
+impl Task {
+    /// Build a new pending task with a fresh UUID from a creation request
+    fn from_request(req: NewTaskRequest) -> Self {
+        let now = now_secs();
+        Task {
+            id: Uuid::new_v4().to_string(),
+            title: req.title.trim().to_string(),
+            description: req.description.trim().to_string(),
+            status: TaskStatus::Pending,
+            created_at: now,
+            assignees: normalize_assignees(req.assignees),
+            priority: req.priority,
+            due_at: req.due_at,
+            tags: normalize_tags(req.tags),
+            history: vec![TaskEvent { at: now, kind: TaskEventKind::Created }],
+            archived: false,
+            comments: Vec::new(),
+            parent_id: req.parent_id,
+            version: 0,
+            recurrence: req.recurrence,
+            metadata: req.metadata,
+            last_activity_at: now,
+            completed_at: None,
+            sort_order: now as f64,
+            blocked_by: Vec::new(),
+            owner: None,
+            estimate_secs: req.estimate_secs,
+            logged_secs: 0,
+        }
+    }
+    
+    /// The next occurrence of a recurring task: a fresh pending copy created `now` and due one
+    /// interval later, keeping the title, description, tags, assignees, priority and estimate.
+    /// Archived tasks don't recur.
+    fn next_occurrence(&self, now: u64) -> Option<Task> {
+        let rule = self.recurrence.as_ref().filter(|rule| rule.interval_secs > 0)?;
+        if self.archived {
+            return None;
+        }
+        Some(Task {
+            id: Uuid::new_v4().to_string(),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            status: TaskStatus::Pending,
+            created_at: now,
+            assignees: self.assignees.clone(),
+            priority: self.priority.clone(),
+            due_at: Some(now.saturating_add(rule.interval_secs)),
+            tags: self.tags.clone(),
+            history: vec![TaskEvent { at: now, kind: TaskEventKind::Created }],
+            archived: false,
+            comments: Vec::new(),
+            parent_id: self.parent_id.clone(),
+            version: 0,
+            recurrence: Some(rule.clone()),
+            metadata: self.metadata.clone(),
+            last_activity_at: now,
+            completed_at: None,
+            sort_order: now as f64,
+            blocked_by: self.blocked_by.clone(),
+            owner: self.owner.clone(),
+            estimate_secs: self.estimate_secs,
+            logged_secs: 0,
+        })
+    }
+    
+    /// Append an event to the task history, dropping the oldest beyond the cap
+    fn record_event(&mut self, kind: TaskEventKind) {
+        self.history.push(TaskEvent { at: now_secs(), kind });
+        if self.history.len() > MAX_HISTORY_EVENTS {
+            let excess = self.history.len() - MAX_HISTORY_EVENTS;
+            self.history.drain(..excess);
+        }
+    }
+    
+    /// Whether moving to `new_status` would start work on the task, which its blockers prevent
+    fn would_start(&self, new_status: &TaskStatus) -> bool {
+        *new_status == TaskStatus::InProgress && self.status != TaskStatus::InProgress
+    }
+    
+    /// Change the status, recording the change in history
+    fn set_status(&mut self, new_status: TaskStatus) {
+        if self.status != new_status {
+            if new_status == TaskStatus::Completed {
+                self.completed_at = Some(now_secs());
+            }
+            let from = std::mem::replace(&mut self.status, new_status.clone());
+            self.record_event(TaskEventKind::StatusChanged { from, to: new_status });
+        }
+    }
+    
+    /// When the task was completed, falling back to its history for tasks completed before
+    /// `completed_at` was tracked. None for tasks that aren't completed.
+    fn completion_time(&self) -> Option<u64> {
+        if self.status != TaskStatus::Completed {
+            return None;
+        }
+        self.completed_at.or_else(|| {
+            self.history.iter().rev().find_map(|event| match event.kind {
+                TaskEventKind::StatusChanged { to: TaskStatus::Completed, .. } => Some(event.at),
+                _ => None,
+            })
+        })
+    }
+    
+    /// Replace the assignees, recording the change in history. Returns the newly added assignees.
+    fn set_assignees(&mut self, assignees: Vec<String>) -> Vec<String> {
+        let assignees = normalize_assignees(assignees);
+        if self.assignees == assignees {
+            return Vec::new();
+        }
+        let added = assignees
+            .iter()
+            .filter(|assignee| !self.assignees.contains(assignee))
+            .cloned()
+            .collect();
+        let from = std::mem::replace(&mut self.assignees, assignees.clone());
+        self.record_event(TaskEventKind::Reassigned { from, to: assignees });
+        added
+    }
+    
+    /// Bytes of free-form text the task carries, for batch size limits
+    fn text_bytes(&self) -> usize {
+        self.title.len()
+            + self.description.len()
+            + self.tags.iter().map(String::len).sum::<usize>()
+            + self.metadata.iter().map(|(key, value)| key.len() + value.len()).sum::<usize>()
+            + self.comments.iter().map(|comment| comment.body.len()).sum::<usize>()
+    }
+    
+    /// Note a change to the task: bump its version and its last activity time
+    fn mark_modified(&mut self) {
+        self.version += 1;
+        self.last_activity_at = now_secs();
+    }
+    
+    /// Fill in fields missing from tasks stored before they existed: creation counts as the
+    /// last activity, and the manual order starts out as creation order
+    fn backfill_defaults(&mut self) {
+        if self.last_activity_at == 0 {
+            self.last_activity_at = self.created_at;
+        }
+        if self.sort_order.is_nan() {
+            self.sort_order = self.created_at as f64;
+        }
+    }
+    
+    /// Reject an update made against a stale copy of the task. No expected version skips the check.
+    fn check_version(&self, expected: Option<u64>) -> Result<(), String> {
+        match expected {
+            Some(expected) if expected != self.version => Err(format!(
+                "Version conflict: expected version {}, task is at version {}",
+                expected, self.version
+            )),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Reject a change by someone other than the owner or an admin, including anonymous callers
+    /// (`caller` None). Unowned tasks are open to all.
+    fn check_owner(&self, caller: Option<&str>, admins: &[String]) -> Result<(), String> {
+        let Some(owner) = &self.owner else {
+            return Ok(());
+        };
+        match caller {
+            Some(caller) if caller == owner || admins.iter().any(|admin| admin == caller) => Ok(()),
+            Some(caller) => Err(format!(
+                "Only the task's owner ({}) or an admin may change it; you are {}",
+                owner, caller
+            )),
+            None => Err(format!(
+                "Only the task's owner ({}) or an admin may change it; anonymous callers may only change unowned tasks",
+                owner
+            )),
+        }
+    }
+    
+    /// A task is overdue when it has a deadline in the past and is still open
+    fn is_overdue(&self, now: u64) -> bool {
+        !matches!(self.status, TaskStatus::Completed | TaskStatus::Cancelled)
+            && self.due_at.is_some_and(|due_at| due_at < now)
+    }
 }
 
 // Define application state
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct TaskManagerState {
     // In-memory task storage
     tasks: HashMap<String, Task>,
     
-    // Track active WebSocket connections for real-time updates
-    active_ws_connections: HashMap<u32, String>, // channel_id -> client_id
+    // Ids of tasks in each status, kept in step with `tasks` by insert_task/remove_task/
+    // reindex_status. Only `tasks` is persisted; the index is rebuilt on startup.
+    #[serde(skip)]
+    status_index: HashMap<TaskStatus, HashSet<String>>,
+    
+    // Track active WebSocket connections for real-time updates.
+    // Channels don't survive a restart, so they're never persisted.
+    #[serde(skip)]
+    active_ws_connections: HashMap<u32, WsConnection>, // channel_id -> connection
+    
+    // Inbound message counts per WebSocket channel, subscribed or not
+    #[serde(skip)]
+    ws_rate: HashMap<u32, RateState>,
+    
+    // Sequence number of the last broadcast WebSocket event. Restarts from 0 with each run.
+    #[serde(skip)]
+    ws_seq: u64,
+    
+    // Random id of this run, drawn at startup, so resuming clients can tell whether their
+    // sequence number came from it
+    #[serde(skip)]
+    ws_run_id: String,
+    
+    // The most recent broadcast events with their sequence numbers, oldest first, for replay to
+    // clients resuming a subscription. Capped at WS_REPLAY_EVENTS.
+    #[serde(skip)]
+    ws_replay: VecDeque<(u64, ServerWsEvent)>,
     
     // Analytics
     request_count: u64,
     task_creation_count: u64,
+    // Per-endpoint hit counts, keyed by handler name
+    #[serde(default)]
+    endpoint_hits: HashMap<String, u64>,
+    
+    // Set when storage was unreachable during startup so the tick retries the load
+    #[serde(skip)]
+    storage_load_pending: bool,
+    
+    // Ids of overdue tasks that WebSocket clients have already been told about
+    #[serde(default)]
+    notified_overdue: HashSet<String>,
+    
+    // Full address (node@process) of the task storage process; set during initialize
+    #[serde(default)]
+    storage_address: Option<String>,
+    
+    // Unix seconds when this process instance started, for uptime reporting
+    #[serde(skip)]
+    started_at: u64,
+    
+    // Whether initialize adds a "Welcome Task" when there are no tasks at all
+    #[serde(default = "default_seed_welcome_task")]
+    seed_welcome_task: bool,
+    
+    // Attempts made for each storage call before giving up. Task stores get one attempt, with
+    // later ones left to the tick's sweep, so replies aren't held up by retries.
+    #[serde(default = "default_storage_attempts")]
+    storage_attempts: u32,
+    
+    // Whether tasks are sent to storage packed as MessagePack when the storage process supports it
+    #[serde(default)]
+    compact_storage: bool,
+    
+    // Whether the storage process listed packed tasks among its capabilities when last asked
+    #[serde(skip)]
+    storage_accepts_packed: bool,
+    
+    // Ids of tasks whose latest version couldn't be stored; retried on every tick
+    #[serde(default)]
+    pending_storage_sync: Vec<String>,
+    
+    // Idempotency key -> id of the task created for it, pruned by prune_idempotency_keys
+    #[serde(default)]
+    idempotency_keys: HashMap<String, String>,
+    
+    // Counts last sent in a `StatsUpdated` event; None when no client wants stats
+    #[serde(skip)]
+    last_broadcast_counts: Option<TaskCounts>,
+    
+    // Token WebSocket clients must present to subscribe; None leaves subscriptions open.
+    // Never logged.
+    #[serde(default)]
+    ws_auth_token: Option<String>,
+    
+    // Bumped on every change to `tasks`, so pollers can tell whether anything changed since
+    // the epoch they last saw (see `get_all_tasks_if_changed`)
+    #[serde(default)]
+    state_epoch: u64,
+    
+    // Deleted task ids, oldest first, for clients syncing with `get_tasks_changed_since`.
+    // Capped at MAX_TOMBSTONES and pruned after TOMBSTONE_RETENTION_SECS.
+    #[serde(default)]
+    tombstones: Vec<Tombstone>,
+    
+    // Most tasks kept at once; creating beyond it evicts finished tasks (see `make_room`)
+    #[serde(default)]
+    max_tasks: Option<usize>,
+    
+    // Identities (see `caller_identity`) allowed to change any task, not just their own
+    #[serde(default)]
+    admins: Vec<String>,
+    
+    // What an assignee must look like; free-form unless set to require addresses
+    #[serde(default)]
+    assignee_format: AssigneeFormat,
+    
+    // How long Completed tasks stay active before the tick archives them; None keeps them
+    #[serde(default)]
+    completed_retention_secs: Option<u64>,
+    
+    // Most WebSocket clients subscribed at once; further subscriptions get `ServerBusy`
+    #[serde(default = "default_max_ws_connections")]
+    max_ws_connections: usize,
+    
+    // Reusable task blueprints, by name
+    #[serde(default)]
+    templates: HashMap<String, TaskTemplate>,
+    
+    // Correlation id of the last request whose task was handed to storage, for the test suite
+    #[serde(skip)]
+    last_storage_correlation_id: Option<String>,
+}
+
+fn default_seed_welcome_task() -> bool {
+    true
+}
+
+fn default_storage_attempts() -> u32 {
+    STORAGE_ATTEMPTS
 }
 
+fn default_max_ws_connections() -> usize {
+    MAX_WS_CONNECTIONS
+}
+
+impl Default for TaskManagerState {
+    fn default() -> Self {
+        TaskManagerState {
+            tasks: HashMap::new(),
+            status_index: HashMap::new(),
+            active_ws_connections: HashMap::new(),
+            ws_rate: HashMap::new(),
+            ws_seq: 0,
+            ws_run_id: String::new(),
+            ws_replay: VecDeque::new(),
+            request_count: 0,
+            task_creation_count: 0,
+            endpoint_hits: HashMap::new(),
+            storage_load_pending: false,
+            notified_overdue: HashSet::new(),
+            storage_address: None,
+            started_at: 0,
+            seed_welcome_task: default_seed_welcome_task(),
+            storage_attempts: default_storage_attempts(),
+            compact_storage: false,
+            storage_accepts_packed: false,
+            pending_storage_sync: Vec::new(),
+            idempotency_keys: HashMap::new(),
+            last_broadcast_counts: None,
+            ws_auth_token: None,
+            state_epoch: 0,
+            tombstones: Vec::new(),
+            max_tasks: None,
+            admins: Vec::new(),
+            assignee_format: AssigneeFormat::default(),
+            completed_retention_secs: None,
+            max_ws_connections: default_max_ws_connections(),
+            templates: HashMap::new(),
+            last_storage_correlation_id: None,
+        }
+    }
+}
+
+/// How often the framework persists state. `save_config` is read by the hyperprocess macro at
+/// compile time, so the policy is picked with the `durable` cargo feature rather than at runtime.
+/// `durable` saves after every message, so a crash loses nothing but every request pays for a
+/// state write; the default saves every fifth message, trading up to four messages of state on
+/// a crash for less write overhead.
+#[cfg(feature = "durable")]
+const SAVE_POLICY: SaveOptions = SaveOptions::EveryMessage;
+#[cfg(not(feature = "durable"))]
+const SAVE_POLICY: SaveOptions = SaveOptions::EveryNMessage(5);
+
 // Implement the application logic
 #[hyperprocess(
     name = "Task Manager",
@@ -48,7 +558,8 @@ struct TaskManagerState {
     widget = "task-widget",
     ui = Some(HttpBindingConfig::new(true, true, false, None)),
     endpoints = vec![
-        // Main API endpoint
+        // Main API endpoint. Bound without login, so HTTP callers are anonymous and can only
+        // change unowned tasks (see `caller_identity`).
         Binding::Http {
             path: "/api/tasks",
             config: HttpBindingConfig::new(false, false, false, None)
@@ -59,263 +570,5195 @@ struct TaskManagerState {
             config: WsBindingConfig::new(false, false, false)
         }
     ],
-    save_config = SaveOptions::EveryNMessage(5),
+    save_config = SAVE_POLICY,
     wit_world = "task-manager-dot-os-v0"
 )]
 impl TaskManagerState {
     /// Initialize the process on startup
     #[init]
     async fn initialize(&mut self) {
-        // Simulate loading some initial data
-        let default_task = Task {
-            id: Uuid::new_v4().to_string(),
-            title: "Welcome Task".to_string(),
-            description: "This is your first task!".to_string(),
-            status: TaskStatus::Pending,
-            created_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-            assigned_to: None,
-        };
+        self.started_at = now_secs();
+        self.ws_run_id = Uuid::new_v4().to_string();
+        
+        // The status index isn't persisted, so derive it from the restored tasks
+        self.rebuild_status_index();
+        for task in self.tasks.values_mut() {
+            task.backfill_defaults();
+        }
         
-        self.tasks.insert(default_task.id.clone(), default_task);
+        // Keep a previously configured storage process, otherwise use the one on this node
+        if self.storage_address.is_none() {
+            let our_node = hyperware_process_lib::our().node;
+            self.storage_address = Some(format!("{}@{}", our_node, DEFAULT_STORAGE_PROCESS));
+        }
         
         // Perform any async initialization with other processes
-        match get_stored_tasks().await {
-            Ok(stored_tasks) => {
-                for task in stored_tasks {
-                    self.tasks.insert(task.id.clone(), task);
-                }
-                hyperware_process_lib::logging::info!("Loaded {} tasks from storage", stored_tasks.len());
-            }
-            Err(e) => {
-                hyperware_process_lib::logging::warn!("Failed to load tasks from storage: {:?}", e);
-            }
+        self.negotiate_storage_encoding().await;
+        self.load_stored_tasks().await;
+        
+        // Greet brand-new installs. Skipped while storage is unreachable, since it may
+        // still hold tasks, and once any task exists so restarts don't pile up duplicates.
+        if self.seed_welcome_task && self.tasks.is_empty() && !self.storage_load_pending {
+            let welcome_task = Task::from_request(NewTaskRequest {
+                title: "Welcome Task".to_string(),
+                description: "This is your first task!".to_string(),
+                ..Default::default()
+            });
+            self.insert_task(welcome_task);
         }
+        
+        // Start the periodic maintenance timer
+        request_tick();
     }
     
-    /// Create a new task via HTTP endpoint
+    /// Create a new task via HTTP endpoint, or from another process via local or remote request.
+    /// `JsonReply` serializes as the bare `TaskResponse`, so process callers get that back.
     #[http]
-    async fn create_task(&mut self, new_task_req: NewTaskRequest) -> TaskResponse {
-        self.request_count += 1;
+    #[local]
+    #[remote]
+    async fn create_task(&mut self, new_task_req: NewTaskRequest) -> JsonReply<TaskResponse> {
+        self.record_request("create_task");
+        JsonReply(self.create_from_request(new_task_req).await)
+    }
+    
+    /// Create a task from a `NewTaskRequest` sent as JSON text via HTTP endpoint. A body that
+    /// isn't valid JSON or isn't a valid request gets a `BadRequestBody` rather than the
+    /// framework's plain-text error (see `ParsedReply`).
+    ///
+    /// That only covers the text inside a well-formed `{"CreateTaskFromJson": "..."}` request.
+    /// A POST to `/api/tasks` whose body isn't a valid request at all is rejected by the
+    /// framework before any handler runs, and still gets its plain-text error.
+    #[http]
+    async fn create_task_from_json(&mut self, body: String) -> ParsedReply<TaskResponse> {
+        self.record_request("create_task_from_json");
         
-        // Generate new task with UUID
-        let task_id = Uuid::new_v4().to_string();
-        let task = Task {
-            id: task_id.clone(),
-            title: new_task_req.title,
-            description: new_task_req.description,
-            status: TaskStatus::Pending,
-            created_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-            assigned_to: new_task_req.assigned_to,
+        match serde_json::from_str::<NewTaskRequest>(&body) {
+            Ok(new_task_req) => ParsedReply::Parsed(self.create_from_request(new_task_req).await),
+            Err(e) => ParsedReply::Malformed(BadRequestBody::new(format!("Invalid task request: {}", e))),
+        }
+    }
+    
+    /// Save a task template via HTTP endpoint, replacing any template with the same name.
+    /// Returns false if the name is empty or the template couldn't create a valid task.
+    #[http]
+    fn save_template(&mut self, name: String, template: TaskTemplate) -> bool {
+        self.record_request("save_template");
+        
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return false;
+        }
+        if let Err(reason) = validate_text(&template.title, &template.description) {
+            hyperware_process_lib::logging::warn!("Rejected template {:?}: {}", name, reason);
+            return false;
+        }
+        self.templates.insert(name, template);
+        true
+    }
+    
+    /// Get every task template with its name via HTTP endpoint, in name order
+    #[http]
+    fn list_templates(&mut self) -> Vec<(String, TaskTemplate)> {
+        self.record_request("list_templates");
+        let mut templates: Vec<(String, TaskTemplate)> = self.templates.clone().into_iter().collect();
+        templates.sort_by(|a, b| a.0.cmp(&b.0));
+        templates
+    }
+    
+    /// Delete a task template via HTTP endpoint. Returns false if there was no such template.
+    #[http]
+    fn delete_template(&mut self, name: String) -> bool {
+        self.record_request("delete_template");
+        self.templates.remove(name.trim()).is_some()
+    }
+    
+    /// Create a task from a saved template via HTTP endpoint. A non-empty title, description or
+    /// tag list in `overrides` replaces the template's; its remaining fields (assignees, due date,
+    /// parent, ...) apply as in `create_task`. The priority override is the separate third
+    /// argument, `priority`: `NewTaskRequest.priority` always holds a value, so it can't say
+    /// "keep the template's". `overrides.priority` is therefore ignored; `Some(priority)`
+    /// replaces the template's priority and `None` keeps it.
+    #[http]
+    async fn create_from_template(
+        &mut self,
+        template_name: String,
+        overrides: NewTaskRequest,
+        priority: Option<TaskPriority>,
+    ) -> TaskResponse {
+        self.record_request("create_from_template");
+        
+        let Some(template) = self.templates.get(template_name.trim()) else {
+            return TaskResponse::bad_request(format!("Template {} not found", template_name));
+        };
+        let req = template.apply(overrides, priority);
+        self.create_from_request(req).await
+    }
+    
+    /// Create a new pending task from an existing one via HTTP endpoint. The copy gets the title
+    /// (prefixed with "Copy of "), description, priority, tags and assignees, and nothing else.
+    #[http]
+    async fn duplicate_task(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("duplicate_task");
+        
+        let Some(source) = self.tasks.get(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        let req = NewTaskRequest {
+            title: format!("Copy of {}", source.title),
+            description: source.description.clone(),
+            assignees: source.assignees.clone(),
+            priority: source.priority.clone(),
+            tags: source.tags.clone(),
+            ..Default::default()
         };
+        if let Err(message) = req.validate().and_then(|()| self.assignee_format.check(&req.assignees)) {
+            return TaskResponse::bad_request(message);
+        }
         
-        // Store task locally
-        self.tasks.insert(task_id.clone(), task.clone());
+        let task = Task { owner: caller_identity(), ..Task::from_request(req) };
+        if let Err(message) = self.make_room(std::slice::from_ref(&task)).await {
+            return TaskResponse::conflict(None, message);
+        }
+        self.insert_task(task.clone());
         self.task_creation_count += 1;
         
-        // Asynchronously store in the persistent storage process
-        let storage_result = store_task_in_storage(&task).await;
-        
-        // Notify connected WebSocket clients about the new task
+        let storage_result = self.persist_task(&task).await;
         self.broadcast_task_update(&task);
+        self.broadcast_stats();
+        notify_assignees(&task, &task.assignees);
+        hyperware_process_lib::logging::info!("Created task {} as a copy of {}", task.id, task_id);
         
-        // Return response with task info and storage status
-        TaskResponse {
-            success: true,
-            task: Some(task),
-            storage_status: storage_result.is_ok(),
-            message: "Task created successfully".to_string(),
-        }
-    }
-    
-    /// Get a list of all tasks via HTTP endpoint
-    #[http]
-    fn get_all_tasks(&mut self) -> Vec<Task> {
-        self.request_count += 1;
-        self.tasks.values().cloned().collect()
+        TaskResponse::ok(task, storage_result.is_ok(), "Task duplicated")
     }
     
-    /// Get a specific task by ID via HTTP endpoint
+    /// Create several tasks at once via HTTP endpoint, persisting them in a single storage call.
+    /// A batch over MAX_BATCH_LEN tasks or MAX_BATCH_TEXT_BYTES of text is rejected whole.
     #[http]
-    fn get_task(&mut self, task_id: String) -> TaskResponse {
-        self.request_count += 1;
+    async fn create_tasks_bulk(&mut self, reqs: Vec<NewTaskRequest>) -> BulkCreateResponse {
+        self.record_request("create_tasks_bulk");
         
-        match self.tasks.get(&task_id) {
-            Some(task) => TaskResponse {
-                success: true,
-                task: Some(task.clone()),
-                storage_status: true,
-                message: "Task found".to_string(),
-            },
-            None => TaskResponse {
-                success: false,
-                task: None,
-                storage_status: true,
-                message: "Task not found".to_string(),
-            },
+        // Reject invalid requests up front, then store the rest locally
+        let BulkCreateResponse { mut created, mut failed } = self.prepare_bulk(reqs);
+        if let Err(reason) = self.make_room(&created).await {
+            failed.push(reason);
+            created.clear();
+        }
+        for task in &created {
+            self.insert_task(task.clone());
+        }
+        self.task_creation_count += created.len() as u64;
+        
+        failed.extend(self.persist_batch(&created).await);
+        
+        // Send one snapshot and one stats event rather than an update per task
+        if !created.is_empty() {
+            self.broadcast_snapshot();
+            self.broadcast_stats();
         }
+        for task in &created {
+            notify_assignees(task, &task.assignees);
+        }
+        hyperware_process_lib::logging::info!(
+            "Bulk created {} tasks ({} failures)",
+            created.len(),
+            failed.len()
+        );
+        
+        BulkCreateResponse { created, failed }
     }
     
-    /// Update a task's status via HTTP endpoint
+    /// Restore previously exported tasks via HTTP endpoint, keeping their ids, history and
+    /// timestamps. Tasks whose id is already taken are skipped or given a fresh id according
+    /// to `on_conflict`. Imports don't count as creations in the statistics, but do count against
+    /// `max_tasks` like `create_tasks_bulk`: the whole import is rejected if there isn't room.
     #[http]
-    async fn update_task_status(&mut self, update_req: TaskStatusUpdateRequest) -> TaskResponse {
-        self.request_count += 1;
+    async fn import_tasks(&mut self, tasks: Vec<Task>, on_conflict: ImportConflict) -> BulkCreateResponse {
+        self.record_request("import_tasks");
         
-        if let Some(task) = self.tasks.get_mut(&update_req.task_id) {
-            task.status = update_req.new_status;
-            
-            // Store updated task in storage
-            let storage_result = store_task_in_storage(task).await;
-            
-            // Notify connected clients
-            self.broadcast_task_update(task);
-            
-            TaskResponse {
-                success: true,
-                task: Some(task.clone()),
-                storage_status: storage_result.is_ok(),
-                message: "Task updated successfully".to_string(),
+        let text_bytes = tasks.iter().map(Task::text_bytes).sum();
+        if let Err(reason) = check_batch_limits(tasks.len(), text_bytes) {
+            return BulkCreateResponse { created: Vec::new(), failed: vec![reason] };
+        }
+        
+        let mut created: Vec<Task> = Vec::new();
+        let mut failed = Vec::new();
+        let mut seen_ids = HashSet::new();
+        for (index, mut task) in tasks.into_iter().enumerate() {
+            // Status is checked when the request is deserialized; text limits are checked here
+            if let Err(reason) = validate_text(&task.title, &task.description)
+                .and_then(|()| validate_metadata(&task.metadata))
+                .and_then(|()| self.assignee_format.check(&task.assignees))
+            {
+                failed.push(format!("#{}: {}", index, reason));
+                continue;
             }
-        } else {
-            TaskResponse {
-                success: false,
-                task: None,
-                storage_status: false,
-                message: "Task not found".to_string(),
+            if self.tasks.contains_key(&task.id) || seen_ids.contains(&task.id) {
+                match on_conflict {
+                    ImportConflict::Skip => {
+                        failed.push(format!("#{}: task {} already exists; skipped", index, task.id));
+                        continue;
+                    }
+                    ImportConflict::RegenerateId => task.id = Uuid::new_v4().to_string(),
+                }
             }
+            seen_ids.insert(task.id.clone());
+            task.backfill_defaults();
+            created.push(task);
+        }
+        if let Err(reason) = self.make_room(&created).await {
+            failed.push(reason);
+            created.clear();
+        }
+        for task in &created {
+            self.insert_task(task.clone());
+        }
+        
+        failed.extend(self.persist_batch(&created).await);
+        
+        if !created.is_empty() {
+            self.broadcast_snapshot();
+            self.broadcast_stats();
         }
+        hyperware_process_lib::logging::info!(
+            "Imported {} tasks ({} failures)",
+            created.len(),
+            failed.len()
+        );
+        
+        BulkCreateResponse { created, failed }
     }
     
-    /// Handle local request to get task statistics
-    #[local]
-    fn get_statistics(&mut self) -> TaskManagerStats {
-        TaskManagerStats {
-            total_tasks: self.tasks.len() as u64,
-            pending_tasks: self.tasks.values().filter(|t| matches!(t.status, TaskStatus::Pending)).count() as u64,
-            completed_tasks: self.tasks.values().filter(|t| matches!(t.status, TaskStatus::Completed)).count() as u64,
-            creation_count: self.task_creation_count,
-            request_count: self.request_count,
+    /// Check a batch of tasks via HTTP endpoint without creating them. `created` lists the tasks
+    /// `create_tasks_bulk` would create; nothing is stored, persisted or broadcast.
+    #[http]
+    fn validate_tasks_bulk(&mut self, reqs: Vec<NewTaskRequest>) -> BulkCreateResponse {
+        self.record_request("validate_tasks_bulk");
+        self.prepare_bulk(reqs)
+    }
+    
+    /// Get a list of all tasks via HTTP endpoint
+    #[http]
+    fn get_all_tasks(&mut self) -> JsonReply<Vec<Task>> {
+        self.record_request("get_all_tasks");
+        JsonReply(self.active_tasks().cloned().collect())
+    }
+    
+    /// Get all tasks via HTTP endpoint, like `get_all_tasks`, unless nothing changed since
+    /// `since_epoch`. Clients pass the epoch from their last reply; any other epoch, including
+    /// one from before a restart, gets the full list.
+    #[http]
+    fn get_all_tasks_if_changed(&mut self, since_epoch: u64) -> TasksSince {
+        self.record_request("get_all_tasks_if_changed");
+        
+        let epoch = self.state_epoch;
+        if since_epoch == epoch {
+            return TasksSince::Unchanged { epoch };
         }
+        TasksSince::Changed { epoch, tasks: self.active_tasks().cloned().collect() }
     }
     
-    /// Handle both local and remote requests to get tasks by status
-    #[local]
-    #[remote]
-    fn get_tasks_by_status(&mut self, status: TaskStatus) -> Vec<Task> {
-        self.tasks
+    /// Get tasks changed or touched after `since` (unix seconds) via HTTP endpoint, least
+    /// recently active first. Archived tasks are included so clients see them being archived.
+    /// Deleted tasks can't appear here; clients learn about those from `get_tombstones_since`.
+    #[http]
+    fn get_tasks_changed_since(&mut self, since: u64) -> Vec<Task> {
+        self.record_request("get_tasks_changed_since");
+        
+        let mut tasks: Vec<&Task> = self
+            .tasks
             .values()
-            .filter(|task| task.status == status)
+            .filter(|task| task.last_activity_at > since)
+            .collect();
+        tasks.sort_by(|a, b| a.last_activity_at.cmp(&b.last_activity_at).then_with(|| a.id.cmp(&b.id)));
+        tasks.into_iter().cloned().collect()
+    }
+    
+    /// Get tasks deleted after `since` (unix seconds) via HTTP endpoint, oldest first. Deletions
+    /// older than TOMBSTONE_RETENTION_SECS are forgotten, so clients that last synced before
+    /// then should reload everything.
+    #[http]
+    fn get_tombstones_since(&mut self, since: u64) -> Vec<Tombstone> {
+        self.record_request("get_tombstones_since");
+        
+        self.tombstones
+            .iter()
+            .filter(|tombstone| tombstone.deleted_at > since)
             .cloned()
             .collect()
     }
     
-    /// Handle WebSocket messages for real-time updates
-    #[ws]
-    fn handle_websocket(&mut self, channel_id: u32, message_type: WsMessageType, blob: LazyLoadBlob) {
-        match message_type {
-            WsMessageType::Binary => {
-                // Handle binary message (example: could be task updates from clients)
-                if let Ok(ws_message) = serde_json::from_slice::<WebSocketMessage>(blob.bytes()) {
-                    match ws_message {
-                        WebSocketMessage::Subscribe { client_id } => {
-                            // Register client for updates
-                            self.active_ws_connections.insert(channel_id, client_id);
-                            
-                            // Send current tasks as initial data
-                            if let Some(server) = hyperware_app_common::get_server() {
-                                let tasks = self.get_all_tasks();
-                                if let Ok(tasks_json) = serde_json::to_vec(&tasks) {
-                                    let _ = server.send_ws_message(channel_id, WsMessageType::Binary, tasks_json);
-                                }
-                            }
-                        }
-                        WebSocketMessage::Unsubscribe => {
-                            // Remove client subscription
-                            self.active_ws_connections.remove(&channel_id);
-                        }
-                    }
-                }
-            }
-            WsMessageType::Close => {
-                // Client disconnected, remove from active connections
-                self.active_ws_connections.remove(&channel_id);
-            }
-            _ => { /* Ignore other message types */ }
-        }
+    /// Get the `limit` most recent history events across all tasks via HTTP endpoint, newest
+    /// first, for activity feeds. Archived tasks are included. Events in the same second are
+    /// ordered by task id, and newest first within a task. Fewer entries are returned when fewer
+    /// events exist; each task keeps at most MAX_HISTORY_EVENTS.
+    #[http]
+    fn get_recent_activity(&mut self, limit: usize) -> Vec<ActivityEntry> {
+        self.record_request("get_recent_activity");
+        
+        let mut events: Vec<(&Task, usize, &TaskEvent)> = self
+            .tasks
+            .values()
+            .flat_map(|task| task.history.iter().enumerate().map(move |(index, event)| (task, index, event)))
+            .collect();
+        events.sort_by(|(a, a_index, a_event), (b, b_index, b_event)| {
+            b_event.at.cmp(&a_event.at).then_with(|| a.id.cmp(&b.id)).then_with(|| b_index.cmp(a_index))
+        });
+        events
+            .into_iter()
+            .take(limit)
+            .map(|(task, _, event)| ActivityEntry {
+                task_id: task.id.clone(),
+                title: task.title.clone(),
+                kind: event.kind.clone(),
+                at: event.at,
+            })
+            .collect()
     }
     
-    // Helper method to broadcast updates to all connected WebSocket clients
-    fn broadcast_task_update(&self, task: &Task) {
-        if let Some(server) = hyperware_app_common::get_server() {
-            if let Ok(task_json) = serde_json::to_vec(&task) {
-                for channel_id in self.active_ws_connections.keys() {
-                    let _ = server.send_ws_message(*channel_id, WsMessageType::Binary, task_json.clone());
-                }
-            }
-        }
+    /// Get the id, title and status of every task via HTTP endpoint, newest first, for pickers
+    /// that don't need whole tasks. Archived tasks are included, so the count matches the store.
+    #[http]
+    fn list_task_summaries(&mut self) -> Vec<TaskSummary> {
+        self.record_request("list_task_summaries");
+        
+        let mut tasks: Vec<&Task> = self.tasks.values().collect();
+        tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
+        tasks
+            .into_iter()
+            .map(|task| TaskSummary {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                status: task.status.clone(),
+            })
+            .collect()
     }
-}
-
-// Supporting types for the application
+    
+    /// Bump a task's last activity time via HTTP endpoint without changing anything else.
+    /// The version is left alone, so touching never causes a version conflict.
+    #[http]
+    async fn touch_task(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("touch_task");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        task.last_activity_at = now_secs();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Task touched")
+    }
+    
+    /// Move a task to just before `target_id` in the manual order via HTTP endpoint. Only the
+    /// moved task changes, unless its new neighbours are too close together to fit a value
+    /// between them; then every task is renumbered first.
+    #[http]
+    async fn move_task_before(&mut self, task_id: String, target_id: String) -> TaskResponse {
+        self.record_request("move_task_before");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        if task_id == target_id {
+            return TaskResponse::bad_request("A task can't be moved before itself");
+        }
+        if !self.tasks.contains_key(&task_id) {
+            return TaskResponse::not_found(&task_id);
+        }
+        if !self.tasks.contains_key(&target_id) {
+            return TaskResponse::not_found(&target_id);
+        }
+        
+        let sort_order = match self.sort_order_before(&task_id, &target_id) {
+            Some(sort_order) => sort_order,
+            None => {
+                self.renumber_sort_order().await;
+                // Renumbered neighbours are 1.0 apart, so there is always room now
+                self.sort_order_before(&task_id, &target_id)
+                    .unwrap_or_else(|| self.tasks[&target_id].sort_order - 0.5)
+            }
+        };
+        self.move_task_to(&task_id, sort_order).await
+    }
+    
+    /// Move a task to the start of the manual order via HTTP endpoint
+    #[http]
+    async fn move_task_to_top(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("move_task_to_top");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let Some(current) = self.tasks.get(&task_id).map(|task| task.sort_order) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        let sort_order = self
+            .tasks
+            .values()
+            .filter(|task| task.id != task_id)
+            .map(|task| task.sort_order)
+            .min_by(f64::total_cmp)
+            .map_or(current, |first| first - 1.0);
+        self.move_task_to(&task_id, sort_order).await
+    }
+    
+    /// Move a task to the end of the manual order via HTTP endpoint
+    #[http]
+    async fn move_task_to_bottom(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("move_task_to_bottom");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let Some(current) = self.tasks.get(&task_id).map(|task| task.sort_order) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        let sort_order = self
+            .tasks
+            .values()
+            .filter(|task| task.id != task_id)
+            .map(|task| task.sort_order)
+            .max_by(f64::total_cmp)
+            .map_or(current, |last| last + 1.0);
+        self.move_task_to(&task_id, sort_order).await
+    }
+    
+    /// Get a list of all tasks via HTTP endpoint, ordered by `sort`. Ties are broken by id,
+    /// ascending in either direction, so the order is deterministic.
+    #[http]
+    fn get_all_tasks_sorted(&mut self, sort: SortSpec) -> Vec<Task> {
+        self.record_request("get_all_tasks_sorted");
+        
+        let mut tasks: Vec<Task> = self.active_tasks().cloned().collect();
+        tasks.sort_by(|a, b| {
+            let ordering = sort.field.compare(a, b);
+            let ordering = if sort.descending { ordering.reverse() } else { ordering };
+            ordering.then_with(|| a.id.cmp(&b.id))
+        });
+        tasks
+    }
+    
+    /// Export tasks as RFC 4180 CSV via HTTP endpoint, oldest first. Archived tasks are left out,
+    /// matching `get_all_tasks`.
+    #[http]
+    fn export_tasks_csv(&mut self) -> String {
+        self.record_request("export_tasks_csv");
+        
+        let mut csv = String::from("id,title,description,status,created_at,assignees\r\n");
+        for task in self.tasks_in_creation_order().into_iter().filter(|task| !task.archived) {
+            csv.push_str(&format!(
+                "{},{},{},{:?},{},{}\r\n",
+                csv_field(&task.id),
+                csv_field(&task.title),
+                csv_field(&task.description),
+                task.status,
+                task.created_at,
+                csv_field(&task.assignees.join(";")),
+            ));
+        }
+        csv
+    }
+    
+    /// Report liveness and storage connectivity via HTTP endpoint. Probes aren't counted in the
+    /// request statistics, and the storage ping uses a short timeout without retries so the
+    /// check stays quick when storage is offline.
+    #[http]
+    async fn health(&mut self) -> HealthStatus {
+        let storage_reachable = ping_storage(&self.storage_address()).await;
+        
+        HealthStatus {
+            ok: storage_reachable,
+            storage_reachable,
+            task_count: self.tasks.len() as u64,
+            uptime_secs: now_secs().saturating_sub(self.started_at),
+            ws_connection_count: self.active_ws_connections.len() as u64,
+        }
+    }
+    
+    /// Report metrics in the Prometheus text exposition format via HTTP endpoint. Like `health`,
+    /// scrapes aren't counted in the request statistics. Task counts include archived tasks.
+    #[http]
+    fn metrics(&mut self) -> String {
+        let mut out = String::new();
+        push_metric(
+            &mut out,
+            "taskmanager_tasks_total",
+            "gauge",
+            "Tasks currently held, archived included",
+            &[(String::new(), self.tasks.len() as u64)],
+        );
+        let statuses = [TaskStatus::Pending, TaskStatus::InProgress, TaskStatus::Completed, TaskStatus::Cancelled];
+        let by_status: Vec<(String, u64)> = statuses
+            .iter()
+            .map(|status| (format!("status=\"{}\"", status_label(status)), self.status_count(status)))
+            .collect();
+        push_metric(
+            &mut out,
+            "taskmanager_tasks_by_status",
+            "gauge",
+            "Tasks currently held, by status",
+            &by_status,
+        );
+        push_metric(
+            &mut out,
+            "taskmanager_tasks_created_total",
+            "counter",
+            "Tasks created over the process's lifetime, across restarts",
+            &[(String::new(), self.task_creation_count)],
+        );
+        push_metric(
+            &mut out,
+            "taskmanager_requests_total",
+            "counter",
+            "Requests handled",
+            &[(String::new(), self.request_count)],
+        );
+        let mut by_endpoint: Vec<(String, u64)> = self
+            .endpoint_hits
+            .iter()
+            .map(|(endpoint, hits)| (format!("endpoint=\"{}\"", escape_label_value(endpoint)), *hits))
+            .collect();
+        by_endpoint.sort();
+        push_metric(
+            &mut out,
+            "taskmanager_endpoint_requests_total",
+            "counter",
+            "Requests handled, by endpoint",
+            &by_endpoint,
+        );
+        push_metric(
+            &mut out,
+            "taskmanager_ws_connections",
+            "gauge",
+            "Open WebSocket connections",
+            &[(String::new(), self.active_ws_connections.len() as u64)],
+        );
+        push_metric(
+            &mut out,
+            "taskmanager_storage_pending_sync",
+            "gauge",
+            "Tasks waiting to be written to storage",
+            &[(String::new(), self.pending_storage_sync.len() as u64)],
+        );
+        out
+    }
+    
+    /// Get per-status task counts via HTTP endpoint, without transferring the tasks themselves.
+    /// Archived tasks are not counted, matching `get_all_tasks`.
+    #[http]
+    fn get_counts(&mut self) -> TaskCounts {
+        self.record_request("get_counts");
+        self.task_counts()
+    }
+    
+    /// Get active tasks grouped into one column per status via HTTP endpoint, for kanban views.
+    /// Each column is in manual order, ties broken by creation time then id.
+    #[http]
+    fn get_board(&mut self) -> TaskBoard {
+        self.record_request("get_board");
+        
+        let mut board = TaskBoard::default();
+        for task in self.active_tasks() {
+            let column = match task.status {
+                TaskStatus::Pending => &mut board.pending,
+                TaskStatus::InProgress => &mut board.in_progress,
+                TaskStatus::Completed => &mut board.completed,
+                TaskStatus::Cancelled => &mut board.cancelled,
+            };
+            column.push(task.clone());
+        }
+        for column in [&mut board.pending, &mut board.in_progress, &mut board.completed, &mut board.cancelled] {
+            column.sort_by(|a, b| {
+                a.sort_order
+                    .total_cmp(&b.sort_order)
+                    .then_with(|| a.created_at.cmp(&b.created_at))
+                    .then_with(|| a.id.cmp(&b.id))
+            });
+        }
+        board
+    }
+    
+    /// Get one page of non-archived tasks via HTTP endpoint, ordered by creation time then id.
+    /// `total` counts the non-archived tasks across all pages.
+    #[http]
+    fn get_tasks_paginated(&mut self, req: PageRequest) -> TaskPage {
+        self.record_request("get_tasks_paginated");
+        
+        let active: Vec<&Task> = self
+            .tasks_in_creation_order()
+            .into_iter()
+            .filter(|task| !task.archived)
+            .collect();
+        let tasks = active.iter().skip(req.offset).take(req.limit).map(|task| (*task).clone()).collect();
+        
+        TaskPage {
+            tasks,
+            total: active.len() as u64,
+            offset: req.offset,
+        }
+    }
+    
+    /// Get the non-archived tasks after `cursor` via HTTP endpoint, ordered by creation time then id.
+    /// A `None` cursor starts from the first task; pass back `next_cursor` for the following
+    /// page until it is `None`. Unlike offsets, cursors don't shift when tasks are created or
+    /// deleted between calls. A cursor that can't be decoded yields an empty final page. A
+    /// `limit` of 0 is treated as 1, so paging always moves forward.
+    #[http]
+    fn get_tasks_after(&mut self, cursor: Option<String>, limit: usize) -> CursorPage {
+        self.record_request("get_tasks_after");
+        
+        let after = match cursor.as_deref().map(decode_cursor) {
+            None => None,
+            Some(Some(position)) => Some(position),
+            Some(None) => {
+                hyperware_process_lib::logging::warn!("Rejected malformed task cursor {:?}", cursor);
+                return CursorPage { tasks: Vec::new(), next_cursor: None };
+            }
+        };
+        
+        let mut remaining = self.tasks_in_creation_order().into_iter().filter(|task| {
+            !task.archived
+                && match &after {
+                    Some((created_at, id)) => (task.created_at, task.id.as_str()) > (*created_at, id.as_str()),
+                    None => true,
+                }
+        });
+        let tasks: Vec<Task> = remaining.by_ref().take(limit.max(1)).cloned().collect();
+        let next_cursor = match (remaining.next(), tasks.last()) {
+            (Some(_), Some(last)) => Some(encode_cursor(last)),
+            _ => None,
+        };
+        
+        CursorPage { tasks, next_cursor }
+    }
+    
+    /// Case-insensitive search over task titles and descriptions, and comment bodies when
+    /// `include_comments` is set, via HTTP endpoint. Each result names the first field that
+    /// matched; title matches rank first, then description and comment matches, newest first
+    /// within each group.
+    #[http]
+    fn search_tasks(&mut self, query: String, include_comments: bool) -> Vec<SearchResult> {
+        self.record_request("search_tasks");
+        
+        // An empty query would match everything; return nothing instead of dumping the store
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut matches: Vec<(MatchField, &Task)> = self
+            .active_tasks()
+            .filter_map(|task| {
+                let matched_in = if task.title.to_lowercase().contains(&query) {
+                    MatchField::Title
+                } else if task.description.to_lowercase().contains(&query) {
+                    MatchField::Description
+                } else if include_comments
+                    && task.comments.iter().any(|comment| comment.body.to_lowercase().contains(&query))
+                {
+                    MatchField::Comment
+                } else {
+                    return None;
+                };
+                Some((matched_in, task))
+            })
+            .collect();
+        
+        matches.sort_by(|(a_field, a), (b_field, b)| {
+            a_field
+                .cmp(b_field)
+                .then_with(|| b.created_at.cmp(&a.created_at))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        
+        matches
+            .into_iter()
+            .map(|(matched_in, task)| SearchResult { task: task.clone(), matched_in })
+            .collect()
+    }
+    
+    /// Get active tasks matching every criterion set in `filter` via HTTP or remote request,
+    /// newest first. Tags are normalized and text is matched case-insensitively against titles
+    /// and descriptions; an empty filter returns every active task.
+    #[http]
+    #[remote]
+    fn query_tasks(&mut self, filter: TaskFilter) -> Vec<Task> {
+        self.record_request("query_tasks");
+        
+        let tag = filter.tag.as_deref().map(normalize_tag);
+        let text = filter
+            .text
+            .as_deref()
+            .map(|text| text.trim().to_lowercase())
+            .filter(|text| !text.is_empty());
+        
+        let mut matches: Vec<&Task> = self
+            .active_tasks()
+            .filter(|task| filter.status.as_ref().is_none_or(|status| task.status == *status))
+            .filter(|task| filter.priority.as_ref().is_none_or(|priority| task.priority == *priority))
+            .filter(|task| {
+                filter
+                    .assignee
+                    .as_ref()
+                    .is_none_or(|assignee| task.assignees.contains(assignee))
+            })
+            .filter(|task| tag.as_ref().is_none_or(|tag| task.tags.contains(tag)))
+            .filter(|task| {
+                text.as_ref().is_none_or(|text| {
+                    task.title.to_lowercase().contains(text)
+                        || task.description.to_lowercase().contains(text)
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
+        matches.into_iter().cloned().collect()
+    }
+    
+    /// Get open, non-archived tasks whose due date has passed via HTTP endpoint
+    #[http]
+    fn get_overdue_tasks(&mut self) -> Vec<Task> {
+        self.record_request("get_overdue_tasks");
+        
+        let now = now_secs();
+        self.active_tasks()
+            .filter(|task| task.is_overdue(now))
+            .cloned()
+            .collect()
+    }
+    
+    /// Get tasks created within `[from, to]` (unix seconds, inclusive) via HTTP or remote request,
+    /// oldest first. An inverted range matches nothing.
+    #[http]
+    #[remote]
+    fn get_tasks_in_range(&mut self, from: u64, to: u64) -> Vec<Task> {
+        self.record_request("get_tasks_in_range");
+        
+        if from > to {
+            return Vec::new();
+        }
+        
+        self.tasks_in_creation_order()
+            .into_iter()
+            .filter(|task| !task.archived && (from..=to).contains(&task.created_at))
+            .cloned()
+            .collect()
+    }
+    
+    /// Get a specific task by ID via HTTP endpoint
+    #[http]
+    fn get_task(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("get_task");
+        
+        match self.tasks.get(&task_id) {
+            Some(task) => TaskResponse::ok(task.clone(), true, "Task found"),
+            None => TaskResponse::not_found(&task_id),
+        }
+    }
+    
+    /// Get several tasks by ID via HTTP endpoint. Found tasks keep the order of `ids`;
+    /// unknown ids are listed in `missing`.
+    #[http]
+    fn get_tasks_by_ids(&mut self, ids: Vec<String>) -> TasksByIdsResponse {
+        self.record_request("get_tasks_by_ids");
+        
+        let mut response = TasksByIdsResponse { found: Vec::new(), missing: Vec::new() };
+        for id in ids {
+            match self.tasks.get(&id) {
+                Some(task) => response.found.push(task.clone()),
+                None => response.missing.push(id),
+            }
+        }
+        response
+    }
+    
+    /// Update a task's status via HTTP endpoint, or from another process via local request. Local
+    /// requests act as the sending process, so owners and admins can change owned tasks.
+    #[http]
+    #[local]
+    async fn update_task_status(&mut self, update_req: TaskStatusUpdateRequest) -> TaskResponse {
+        self.record_request("update_task_status");
+        let correlation_id = correlation_id_or_new(update_req.correlation_id);
+        let unmet_blockers = self.unmet_blockers(&update_req.task_id);
+        
+        if let Some(task) = self.tasks.get_mut(&update_req.task_id) {
+            if let Err(message) = task.check_owner(caller_identity().as_deref(), &self.admins) {
+                return TaskResponse::forbidden(task.clone(), message).with_correlation_id(correlation_id);
+            }
+            if let Err(message) = task.check_version(update_req.expected_version) {
+                return TaskResponse::version_conflict(task.clone(), message).with_correlation_id(correlation_id);
+            }
+            if !task.status.can_transition_to(&update_req.new_status) {
+                let message = illegal_transition_message(&task.status, &update_req.new_status);
+                return TaskResponse::illegal_transition(task.clone(), update_req.new_status, message)
+                    .with_correlation_id(correlation_id);
+            }
+            if task.would_start(&update_req.new_status) && !unmet_blockers.is_empty() {
+                return TaskResponse::blocked(task.clone(), unmet_blockers).with_correlation_id(correlation_id);
+            }
+            let previous_status = task.status.clone();
+            task.set_status(update_req.new_status);
+            task.mark_modified();
+            let task = task.clone();
+            self.reindex_status(&task.id, &previous_status, &task.status);
+            
+            // Store updated task in storage
+            let storage_result = self.persist_task_traced(&task, &correlation_id).await;
+            
+            // Notify connected clients
+            self.broadcast_task_update(&task);
+            if previous_status != TaskStatus::Completed {
+                self.spawn_next_occurrence(&task).await;
+            }
+            self.broadcast_stats();
+            hyperware_process_lib::logging::info!(
+                "Task {} status {:?} -> {:?} [{}]",
+                task.id,
+                previous_status,
+                task.status,
+                correlation_id
+            );
+            
+            TaskResponse::ok(task, storage_result.is_ok(), "Task updated successfully")
+                .with_correlation_id(correlation_id)
+        } else {
+            TaskResponse::not_found(&update_req.task_id).with_correlation_id(correlation_id)
+        }
+    }
+    
+    /// Apply several status changes via HTTP endpoint, in request order. Each update is checked
+    /// like `update_task_status`; unknown ids, stale versions and illegal transitions are reported
+    /// in `failed` without stopping the rest. Changed tasks are stored in one storage call and
+    /// clients get one snapshot rather than an update per task.
+    #[http]
+    async fn update_statuses_bulk(&mut self, updates: Vec<TaskStatusUpdateRequest>) -> BulkUpdateResponse {
+        self.record_request("update_statuses_bulk");
+        
+        if let Err(reason) = check_batch_limits(updates.len(), 0) {
+            let failed = updates
+                .into_iter()
+                .map(|update| BulkUpdateFailure::from_response(update.task_id, TaskResponse::bad_request(&reason)))
+                .collect();
+            return BulkUpdateResponse { updated: Vec::new(), failed, unsynced: Vec::new() };
+        }
+        
+        let caller = caller_identity();
+        let mut changed_ids: Vec<String> = Vec::new();
+        let mut failed = Vec::new();
+        let mut occurrences = Vec::new();
+        for update in updates {
+            // Earlier updates in the batch may have completed this task's blockers
+            let unmet_blockers = self.unmet_blockers(&update.task_id);
+            let Some(task) = self.tasks.get_mut(&update.task_id) else {
+                failed.push(BulkUpdateFailure::from_response(
+                    update.task_id.clone(),
+                    TaskResponse::not_found(&update.task_id),
+                ));
+                continue;
+            };
+            if let Err(message) = task.check_owner(caller.as_deref(), &self.admins) {
+                let response = TaskResponse::forbidden(task.clone(), message);
+                failed.push(BulkUpdateFailure::from_response(update.task_id, response));
+                continue;
+            }
+            if let Err(message) = task.check_version(update.expected_version) {
+                let response = TaskResponse::version_conflict(task.clone(), message);
+                failed.push(BulkUpdateFailure::from_response(update.task_id, response));
+                continue;
+            }
+            if !task.status.can_transition_to(&update.new_status) {
+                let message = illegal_transition_message(&task.status, &update.new_status);
+                let response = TaskResponse::illegal_transition(task.clone(), update.new_status, message);
+                failed.push(BulkUpdateFailure::from_response(update.task_id, response));
+                continue;
+            }
+            if task.would_start(&update.new_status) && !unmet_blockers.is_empty() {
+                let response = TaskResponse::blocked(task.clone(), unmet_blockers);
+                failed.push(BulkUpdateFailure::from_response(update.task_id, response));
+                continue;
+            }
+            
+            let previous_status = task.status.clone();
+            task.set_status(update.new_status);
+            task.mark_modified();
+            let task = task.clone();
+            self.reindex_status(&task.id, &previous_status, &task.status);
+            if previous_status != TaskStatus::Completed {
+                occurrences.extend(self.start_next_occurrence(&task));
+            }
+            if !changed_ids.contains(&task.id) {
+                changed_ids.push(task.id);
+            }
+        }
+        
+        // Store each changed task once, at its final version, along with any new occurrences
+        let updated: Vec<Task> = changed_ids.iter().map(|id| self.tasks[id].clone()).collect();
+        let mut to_store = updated.clone();
+        to_store.extend(occurrences);
+        let unsynced = self.persist_batch(&to_store).await;
+        
+        if !to_store.is_empty() {
+            self.bump_epoch();
+            self.broadcast_snapshot();
+            self.broadcast_stats();
+        }
+        hyperware_process_lib::logging::info!(
+            "Bulk updated the status of {} tasks ({} failures)",
+            updated.len(),
+            failed.len()
+        );
+        
+        BulkUpdateResponse { updated, failed, unsynced }
+    }
+    
+    /// Cancel a task via HTTP endpoint, recording an optional reason in its history.
+    /// Tasks that are already Completed or Cancelled can't be cancelled.
+    #[http]
+    async fn cancel_task(&mut self, task_id: String, reason: Option<String>) -> TaskResponse {
+        self.record_request("cancel_task");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let reason = reason
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty());
+        if reason.as_ref().is_some_and(|reason| reason.chars().count() > MAX_COMMENT_LEN) {
+            return TaskResponse::bad_request(format!(
+                "Cancellation reason exceeds {} characters",
+                MAX_COMMENT_LEN
+            ));
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        let terminal = task.status == TaskStatus::Cancelled
+            || !task.status.can_transition_to(&TaskStatus::Cancelled);
+        if terminal {
+            let message = format!("Cannot cancel a task that is {:?}", task.status);
+            return TaskResponse::illegal_transition(task.clone(), TaskStatus::Cancelled, message);
+        }
+        let previous_status = std::mem::replace(&mut task.status, TaskStatus::Cancelled);
+        task.record_event(TaskEventKind::Cancelled { reason });
+        task.mark_modified();
+        let task = task.clone();
+        self.reindex_status(&task.id, &previous_status, &task.status);
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        self.broadcast_stats();
+        hyperware_process_lib::logging::info!("Task {} cancelled from {:?}", task.id, previous_status);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Task cancelled")
+    }
+    
+    /// Update a task's title, description, assignee and/or status via HTTP endpoint.
+    /// Only the fields set in the request are applied. Titles and descriptions are trimmed and
+    /// held to the same limits as in `create_task`.
+    #[http]
+    async fn update_task(&mut self, req: TaskUpdateRequest) -> TaskResponse {
+        self.record_request("update_task");
+        let correlation_id = correlation_id_or_new(req.correlation_id);
+        
+        if let Some(parent_id) = &req.parent_id {
+            if let Err(message) = self.check_reparent(&req.task_id, parent_id) {
+                return TaskResponse::bad_request(message).with_correlation_id(correlation_id);
+            }
+        }
+        if let Some(assignees) = &req.assignees {
+            if let Err(message) = self.assignee_format.check(assignees) {
+                return TaskResponse::bad_request(message).with_correlation_id(correlation_id);
+            }
+        }
+        
+        let unmet_blockers = self.unmet_blockers(&req.task_id);
+        if let Some(task) = self.tasks.get_mut(&req.task_id) {
+            if let Err(message) = task.check_owner(caller_identity().as_deref(), &self.admins) {
+                return TaskResponse::forbidden(task.clone(), message).with_correlation_id(correlation_id);
+            }
+            if let Err(message) = task.check_version(req.expected_version) {
+                return TaskResponse::version_conflict(task.clone(), message).with_correlation_id(correlation_id);
+            }
+            let title = req.title.as_deref().unwrap_or(&task.title);
+            let description = req.description.as_deref().unwrap_or(&task.description);
+            if let Err(message) = validate_text(title, description) {
+                return TaskResponse::bad_request(message).with_correlation_id(correlation_id);
+            }
+            
+            // Reject illegal status changes before touching any field
+            if let Some(new_status) = &req.new_status {
+                if !task.status.can_transition_to(new_status) {
+                    let message = illegal_transition_message(&task.status, new_status);
+                    return TaskResponse::illegal_transition(task.clone(), new_status.clone(), message)
+                        .with_correlation_id(correlation_id);
+                }
+                if task.would_start(new_status) && !unmet_blockers.is_empty() {
+                    return TaskResponse::blocked(task.clone(), unmet_blockers).with_correlation_id(correlation_id);
+                }
+            }
+            
+            let previous_status = task.status.clone();
+            let mut modified = false;
+            if let Some(title) = req.title {
+                task.title = title.trim().to_string();
+                modified = true;
+            }
+            if let Some(description) = req.description {
+                task.description = description.trim().to_string();
+                modified = true;
+            }
+            let mut added_assignees = Vec::new();
+            if let Some(assignees) = req.assignees {
+                added_assignees = task.set_assignees(assignees);
+                modified = true;
+            }
+            if let Some(new_status) = req.new_status {
+                task.set_status(new_status);
+                modified = true;
+            }
+            if let Some(parent_id) = req.parent_id {
+                task.parent_id = Some(parent_id);
+                modified = true;
+            }
+            if !modified {
+                return TaskResponse::ok(task.clone(), true, "No fields provided; task not modified")
+                    .with_correlation_id(correlation_id);
+            }
+            task.mark_modified();
+            let task = task.clone();
+            self.reindex_status(&task.id, &previous_status, &task.status);
+            
+            // Store updated task in storage
+            let storage_result = self.persist_task_traced(&task, &correlation_id).await;
+            
+            // Notify connected clients once, regardless of how many fields changed
+            self.broadcast_task_update(&task);
+            notify_assignees(&task, &added_assignees);
+            if previous_status != TaskStatus::Completed {
+                self.spawn_next_occurrence(&task).await;
+            }
+            self.broadcast_stats();
+            hyperware_process_lib::logging::info!(
+                "Updated task {} to version {} [{}]",
+                task.id,
+                task.version,
+                correlation_id
+            );
+            
+            TaskResponse::ok(task, storage_result.is_ok(), "Task updated successfully")
+                .with_correlation_id(correlation_id)
+        } else {
+            TaskResponse::not_found(&req.task_id).with_correlation_id(correlation_id)
+        }
+    }
+    
+    /// Apply a JSON merge patch to a task via HTTP endpoint, for generic REST tooling. Supported
+    /// keys are `title`, `description`, `status`, `assigned_to` (a name, a list of names or null),
+    /// `priority`, `tags` and `estimate_secs`. With `strict` any other key rejects the patch; otherwise other keys
+    /// are ignored and named in the message. Every field is checked before any is applied, and
+    /// status changes follow the same rules as `update_task_status`.
+    #[http]
+    async fn patch_task(&mut self, task_id: String, patch: serde_json::Value, strict: bool) -> TaskResponse {
+        self.record_request("patch_task");
+        
+        let (patch, ignored) = match TaskPatch::parse(patch, strict) {
+            Ok(parsed) => parsed,
+            Err(message) => return TaskResponse::bad_request(message),
+        };
+        if let Some(assignees) = &patch.assignees {
+            if let Err(message) = self.assignee_format.check(assignees) {
+                return TaskResponse::bad_request(message);
+            }
+        }
+        let unmet_blockers = self.unmet_blockers(&task_id);
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        if let Err(message) = task.check_owner(caller_identity().as_deref(), &self.admins) {
+            return TaskResponse::forbidden(task.clone(), message);
+        }
+        
+        let title = patch.title.as_deref().unwrap_or(&task.title);
+        let description = patch.description.as_deref().unwrap_or(&task.description);
+        if let Err(message) = validate_text(title, description) {
+            return TaskResponse::bad_request(message);
+        }
+        if let Some(new_status) = &patch.status {
+            if !task.status.can_transition_to(new_status) {
+                let message = illegal_transition_message(&task.status, new_status);
+                return TaskResponse::illegal_transition(task.clone(), new_status.clone(), message);
+            }
+            if task.would_start(new_status) && !unmet_blockers.is_empty() {
+                return TaskResponse::blocked(task.clone(), unmet_blockers);
+            }
+        }
+        if patch.is_empty() {
+            return TaskResponse::ok(task.clone(), true, "No fields provided; task not modified");
+        }
+        
+        let previous_status = task.status.clone();
+        if let Some(title) = patch.title {
+            task.title = title.trim().to_string();
+        }
+        if let Some(description) = patch.description {
+            task.description = description.trim().to_string();
+        }
+        let added_assignees = match patch.assignees {
+            Some(assignees) => task.set_assignees(assignees),
+            None => Vec::new(),
+        };
+        if let Some(priority) = patch.priority {
+            task.priority = priority;
+        }
+        if let Some(tags) = patch.tags {
+            task.tags = normalize_tags(tags);
+        }
+        if let Some(estimate_secs) = patch.estimate_secs {
+            task.estimate_secs = estimate_secs;
+        }
+        if let Some(new_status) = patch.status {
+            task.set_status(new_status);
+        }
+        task.mark_modified();
+        let task = task.clone();
+        self.reindex_status(&task.id, &previous_status, &task.status);
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        notify_assignees(&task, &added_assignees);
+        if previous_status != TaskStatus::Completed {
+            self.spawn_next_occurrence(&task).await;
+        }
+        self.broadcast_stats();
+        hyperware_process_lib::logging::info!("Patched task {} to version {}", task.id, task.version);
+        
+        let message = if ignored.is_empty() {
+            "Task patched".to_string()
+        } else {
+            format!("Task patched; ignored unknown keys: {}", ignored.join(", "))
+        };
+        TaskResponse::ok(task, storage_result.is_ok(), message)
+    }
+    
+    /// Reassign a task via HTTP endpoint, replacing all its assignees with `assignee`, recording
+    /// the change in its history and notifying the new assignee. `None` unassigns the task.
+    #[http]
+    async fn reassign_task(&mut self, task_id: String, assignee: Option<String>) -> TaskResponse {
+        self.record_request("reassign_task");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let assignees: Vec<String> = assignee.into_iter().collect();
+        if let Err(message) = self.assignee_format.check(&assignees) {
+            return TaskResponse::bad_request(message);
+        }
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if task.assignees == normalize_assignees(assignees.clone()) {
+            return TaskResponse::ok(task.clone(), true, "Task already has this assignee");
+        }
+        let added = task.set_assignees(assignees);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        notify_assignees(&task, &added);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Task reassigned")
+    }
+    
+    /// Hand every task assigned to `from` over to `to` via HTTP endpoint, or just drop `from` when
+    /// `to` is `None`. Other assignees stay. The changes are recorded in each task's history,
+    /// stored in one batch and broadcast as one snapshot; `updated` lists the reassigned tasks.
+    /// A `to` the assignee format rejects fails every matching task, and tasks the caller may not
+    /// change (see `check_can_change`) fail as forbidden.
+    #[http]
+    async fn reassign_all(&mut self, from: String, to: Option<String>) -> BulkUpdateResponse {
+        self.record_request("reassign_all");
+        
+        let from = from.trim().to_string();
+        let mut matching: Vec<String> = self
+            .tasks
+            .values()
+            .filter(|task| task.assignees.contains(&from))
+            .map(|task| task.id.clone())
+            .collect();
+        matching.sort();
+        if let Err(message) = self.assignee_format.check(to.as_slice()) {
+            let failed = matching
+                .into_iter()
+                .map(|id| BulkUpdateFailure::from_response(id, TaskResponse::bad_request(&message)))
+                .collect();
+            return BulkUpdateResponse { updated: Vec::new(), failed, unsynced: Vec::new() };
+        }
+        
+        let mut updated = Vec::new();
+        let mut failed = Vec::new();
+        let mut notifications = Vec::new();
+        for id in &matching {
+            if let Err(response) = self.check_can_change(id) {
+                failed.push(BulkUpdateFailure::from_response(id.clone(), response));
+                continue;
+            }
+            let Some(task) = self.tasks.get_mut(id) else {
+                continue;
+            };
+            let assignees: Vec<String> = task
+                .assignees
+                .iter()
+                .filter_map(|assignee| if *assignee == from { to.clone() } else { Some(assignee.clone()) })
+                .collect();
+            if task.assignees == normalize_assignees(assignees.clone()) {
+                continue;
+            }
+            let added = task.set_assignees(assignees);
+            task.mark_modified();
+            updated.push(task.clone());
+            notifications.push((task.clone(), added));
+        }
+        
+        let unsynced = self.persist_batch(&updated).await;
+        if !updated.is_empty() {
+            self.bump_epoch();
+            self.broadcast_snapshot();
+            self.broadcast_stats();
+        }
+        for (task, added) in &notifications {
+            notify_assignees(task, added);
+        }
+        hyperware_process_lib::logging::info!("Reassigned {} tasks from {}", updated.len(), from);
+        
+        BulkUpdateResponse { updated, failed, unsynced }
+    }
+    
+    /// Add an assignee to a task via HTTP endpoint, notifying them. Adding an existing assignee
+    /// is a no-op.
+    #[http]
+    async fn add_assignee(&mut self, task_id: String, assignee: String) -> TaskResponse {
+        self.record_request("add_assignee");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let assignee = assignee.trim().to_string();
+        if assignee.is_empty() {
+            return TaskResponse::bad_request("Assignee must not be empty");
+        }
+        if let Err(message) = self.assignee_format.check(std::slice::from_ref(&assignee)) {
+            return TaskResponse::bad_request(message);
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if task.assignees.contains(&assignee) {
+            return TaskResponse::ok(task.clone(), true, "Task already has this assignee");
+        }
+        let mut assignees = task.assignees.clone();
+        assignees.push(assignee);
+        let added = task.set_assignees(assignees);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        notify_assignees(&task, &added);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Assignee added")
+    }
+    
+    /// Remove an assignee from a task via HTTP endpoint. Removing someone who isn't assigned
+    /// is a no-op.
+    #[http]
+    async fn remove_assignee(&mut self, task_id: String, assignee: String) -> TaskResponse {
+        self.record_request("remove_assignee");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let assignee = assignee.trim();
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if !task.assignees.iter().any(|a| a == assignee) {
+            return TaskResponse::ok(task.clone(), true, "Task does not have this assignee");
+        }
+        let assignees = task.assignees.iter().filter(|a| *a != assignee).cloned().collect();
+        task.set_assignees(assignees);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Assignee removed")
+    }
+    
+    /// Get the direct, non-archived subtasks of a task via HTTP endpoint
+    #[http]
+    fn get_subtasks(&mut self, parent_id: String) -> Vec<Task> {
+        self.record_request("get_subtasks");
+        
+        self.active_tasks()
+            .filter(|task| task.parent_id.as_ref() == Some(&parent_id))
+            .cloned()
+            .collect()
+    }
+    
+    /// Get a task's change history via HTTP endpoint, oldest first.
+    /// Unknown ids yield an empty history.
+    #[http]
+    fn get_task_history(&mut self, task_id: String) -> Vec<TaskEvent> {
+        self.record_request("get_task_history");
+        
+        self.tasks
+            .get(&task_id)
+            .map(|task| task.history.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Get non-archived tasks carrying a tag via HTTP endpoint, the same ones `get_tag_cloud`
+    /// counts. The tag is normalized before matching.
+    #[http]
+    fn get_tasks_by_tag(&mut self, tag: String) -> Vec<Task> {
+        self.record_request("get_tasks_by_tag");
+        
+        let tag = normalize_tag(&tag);
+        self.active_tasks()
+            .filter(|task| task.tags.contains(&tag))
+            .cloned()
+            .collect()
+    }
+    
+    /// Get every tag in use on non-archived tasks with the number of tasks carrying it, via HTTP
+    /// endpoint. Sorted by count descending, then tag ascending.
+    #[http]
+    fn get_tag_cloud(&mut self) -> Vec<TagCount> {
+        self.record_request("get_tag_cloud");
+        
+        let mut counts: HashMap<&str, u64> = HashMap::new();
+        for task in self.active_tasks() {
+            for tag in task.tags.iter().filter(|tag| !tag.is_empty()) {
+                *counts.entry(tag.as_str()).or_default() += 1;
+            }
+        }
+        let mut cloud: Vec<TagCount> = counts
+            .into_iter()
+            .map(|(tag, count)| TagCount { tag: tag.to_string(), count })
+            .collect();
+        cloud.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+        cloud
+    }
+    
+    /// Add a tag to a task via HTTP endpoint. Adding a tag the task already has is a no-op.
+    #[http]
+    async fn add_tag(&mut self, task_id: String, tag: String) -> TaskResponse {
+        self.record_request("add_tag");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let tag = normalize_tag(&tag);
+        if tag.is_empty() {
+            return TaskResponse::bad_request("Tag must not be empty");
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if task.tags.contains(&tag) {
+            return TaskResponse::ok(task.clone(), true, "Task already has this tag");
+        }
+        task.tags.push(tag);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Tag added")
+    }
+    
+    /// Remove a tag from a task via HTTP endpoint. Removing an absent tag is a no-op.
+    #[http]
+    async fn remove_tag(&mut self, task_id: String, tag: String) -> TaskResponse {
+        self.record_request("remove_tag");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let tag = normalize_tag(&tag);
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if !task.tags.contains(&tag) {
+            return TaskResponse::ok(task.clone(), true, "Task does not have this tag");
+        }
+        task.tags.retain(|t| t != &tag);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Tag removed")
+    }
+    
+    /// Record via HTTP endpoint that `task_id` can't start until `blocker_id` is Completed.
+    /// Adding an existing dependency is a no-op; one that would form a cycle is rejected.
+    #[http]
+    async fn add_dependency(&mut self, task_id: String, blocker_id: String) -> TaskResponse {
+        self.record_request("add_dependency");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        if let Err(message) = self.check_dependency(&task_id, &blocker_id) {
+            return TaskResponse::bad_request(message);
+        }
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if task.blocked_by.contains(&blocker_id) {
+            return TaskResponse::ok(task.clone(), true, "Task already depends on this task");
+        }
+        task.blocked_by.push(blocker_id);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Dependency added")
+    }
+    
+    /// Remove a dependency via HTTP endpoint. Removing an absent dependency is a no-op.
+    #[http]
+    async fn remove_dependency(&mut self, task_id: String, blocker_id: String) -> TaskResponse {
+        self.record_request("remove_dependency");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if !task.blocked_by.contains(&blocker_id) {
+            return TaskResponse::ok(task.clone(), true, "Task does not depend on this task");
+        }
+        task.blocked_by.retain(|id| id != &blocker_id);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Dependency removed")
+    }
+    
+    /// Get the active tasks with at least one blocker that isn't Completed via HTTP endpoint,
+    /// oldest first
+    #[http]
+    fn get_blocked_tasks(&mut self) -> Vec<Task> {
+        self.record_request("get_blocked_tasks");
+        
+        self.tasks_in_creation_order()
+            .into_iter()
+            .filter(|task| !task.archived && !self.unmet_blockers(&task.id).is_empty())
+            .cloned()
+            .collect()
+    }
+    
+    /// Set a custom metadata field on a task via HTTP endpoint, replacing any previous value.
+    /// Setting a key to the value it already has is a no-op.
+    #[http]
+    async fn set_metadata(&mut self, task_id: String, key: String, value: String) -> TaskResponse {
+        self.record_request("set_metadata");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        if let Err(message) = validate_metadata_entry(&key, &value) {
+            return TaskResponse::bad_request(message);
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if task.metadata.get(&key) == Some(&value) {
+            return TaskResponse::ok(task.clone(), true, "Metadata unchanged");
+        }
+        if !task.metadata.contains_key(&key) && task.metadata.len() >= MAX_METADATA_KEYS {
+            return TaskResponse::bad_request(format!(
+                "Task already has {} metadata keys",
+                MAX_METADATA_KEYS
+            ));
+        }
+        task.metadata.insert(key, value);
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Metadata set")
+    }
+    
+    /// Remove a custom metadata field from a task via HTTP endpoint. Removing an absent key is a no-op.
+    #[http]
+    async fn remove_metadata(&mut self, task_id: String, key: String) -> TaskResponse {
+        self.record_request("remove_metadata");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        if task.metadata.remove(&key).is_none() {
+            return TaskResponse::ok(task.clone(), true, "Task does not have this metadata key");
+        }
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Metadata removed")
+    }
+    
+    /// Delete a task via HTTP endpoint.
+    /// Tasks with subtasks can't be deleted until their subtasks are deleted.
+    #[http]
+    async fn delete_task(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("delete_task");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        if self.tasks.values().any(|task| task.parent_id.as_ref() == Some(&task_id)) {
+            return TaskResponse::conflict(
+                self.tasks.get(&task_id).cloned(),
+                "Task has subtasks; delete them first",
+            );
+        }
+        
+        match self.remove_task(&task_id) {
+            Some(task) => {
+                self.record_tombstone(&task.id);
+                
+                // Remove the task from persistent storage as well
+                let storage_result = delete_task_in_storage(&self.storage(), &task.id).await;
+                if let Err(e) = &storage_result {
+                    log_storage_failure(&format!("delete task {}", task.id), e);
+                }
+                hyperware_process_lib::logging::info!("Deleted task {}", task.id);
+                
+                // Notify connected clients that the task is gone
+                self.broadcast_task_deletion(&task.id);
+                self.broadcast_stats();
+                
+                TaskResponse::ok(task, storage_result.is_ok(), "Task deleted successfully")
+            }
+            None => TaskResponse::not_found(&task_id),
+        }
+    }
+    
+    /// Delete every completed task via HTTP endpoint, archived ones included. Completed tasks
+    /// with subtasks that aren't being deleted too are kept, like `delete_task` would, as are
+    /// those the caller may not change.
+    #[http]
+    async fn clear_completed(&mut self) -> BulkDeleteResponse {
+        self.record_request("clear_completed");
+        
+        // Start from every completed task the caller may delete, then keep any parent whose
+        // subtasks would survive
+        let (mut doomed, forbidden): (HashSet<String>, HashSet<String>) = self
+            .status_index
+            .get(&TaskStatus::Completed)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .partition(|id| self.check_can_change(id).is_ok());
+        loop {
+            let kept: Vec<String> = self
+                .tasks
+                .values()
+                .filter(|task| !doomed.contains(&task.id))
+                .filter_map(|task| task.parent_id.clone())
+                .filter(|parent_id| doomed.contains(parent_id))
+                .collect();
+            if kept.is_empty() {
+                break;
+            }
+            for parent_id in kept {
+                doomed.remove(&parent_id);
+            }
+        }
+        
+        let mut failed: Vec<String> = self
+            .status_index
+            .get(&TaskStatus::Completed)
+            .into_iter()
+            .flatten()
+            .filter(|id| !doomed.contains(*id))
+            .map(|id| {
+                if forbidden.contains(id) {
+                    format!("{}: only its owner or an admin may delete it", id)
+                } else {
+                    format!("{}: has subtasks that aren't completed", id)
+                }
+            })
+            .collect();
+        let mut deleted: Vec<String> = doomed.into_iter().collect();
+        deleted.sort();
+        for task_id in &deleted {
+            self.remove_task(task_id);
+            self.record_tombstone(task_id);
+        }
+        
+        // Storage has no batch delete, so remove the tasks one at a time
+        let storage = self.storage();
+        let mut storage_failures = 0;
+        let mut last_error = None;
+        for task_id in &deleted {
+            if let Err(e) = delete_task_in_storage(&storage, task_id).await {
+                failed.push(format!("{}: failed to delete from storage", task_id));
+                storage_failures += 1;
+                last_error = Some(e);
+            }
+        }
+        if let Some(e) = last_error {
+            let operation = format!("delete {} of {} completed tasks", storage_failures, deleted.len());
+            log_storage_failure(&operation, &e);
+        }
+        hyperware_process_lib::logging::info!("Cleared {} completed tasks", deleted.len());
+        
+        if !deleted.is_empty() {
+            self.broadcast_snapshot();
+            self.broadcast_stats();
+        }
+        
+        BulkDeleteResponse { count: deleted.len() as u64, deleted, failed }
+    }
+    
+    /// Add a comment to a task via HTTP endpoint
+    #[http]
+    async fn add_comment(&mut self, task_id: String, author: String, body: String) -> TaskResponse {
+        self.record_request("add_comment");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let body = body.trim().to_string();
+        if body.is_empty() {
+            return TaskResponse::bad_request("Comment must not be empty");
+        }
+        if body.chars().count() > MAX_COMMENT_LEN {
+            return TaskResponse::bad_request(format!("Comment exceeds {} characters", MAX_COMMENT_LEN));
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        
+        let comment = TaskComment {
+            id: Uuid::new_v4().to_string(),
+            author,
+            body,
+            at: now_secs(),
+        };
+        task.comments.push(comment.clone());
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_event(&ServerWsEvent::CommentAdded { task_id, comment });
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Comment added")
+    }
+    
+    /// Record `secs` of work on a task via HTTP endpoint, adding to its logged effort and noting
+    /// it in history. Logging nothing, or so much the total would overflow, is rejected.
+    #[http]
+    async fn log_work(&mut self, task_id: String, secs: u64) -> TaskResponse {
+        self.record_request("log_work");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        if secs == 0 {
+            return TaskResponse::bad_request("Logged work must be positive");
+        }
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        let Some(logged_secs) = task.logged_secs.checked_add(secs) else {
+            return TaskResponse::bad_request(format!(
+                "Logging {}s would overflow the {}s already logged",
+                secs, task.logged_secs
+            ));
+        };
+        
+        task.logged_secs = logged_secs;
+        task.record_event(TaskEventKind::WorkLogged { secs });
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Work logged")
+    }
+    
+    /// Get a task's comments via HTTP endpoint, oldest first. Unknown ids yield no comments.
+    #[http]
+    fn get_comments(&mut self, task_id: String) -> Vec<TaskComment> {
+        self.record_request("get_comments");
+        
+        self.tasks
+            .get(&task_id)
+            .map(|task| task.comments.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Archive a task via HTTP endpoint, hiding it from default listings while keeping it for audit.
+    /// Tasks with unarchived subtasks can't be archived until their subtasks are.
+    #[http]
+    async fn archive_task(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("archive_task");
+        self.set_archived(&task_id, true).await
+    }
+    
+    /// Restore an archived task to default listings via HTTP endpoint
+    #[http]
+    async fn unarchive_task(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("unarchive_task");
+        self.set_archived(&task_id, false).await
+    }
+    
+    /// Undo a cancellation or archiving via HTTP endpoint. A cancelled task goes back to Pending,
+    /// the one way out of Cancelled, and an archived task is unarchived; a task that is both gets
+    /// both. Other tasks are left alone with a conflict.
+    #[http]
+    async fn restore_task(&mut self, task_id: String) -> TaskResponse {
+        self.record_request("restore_task");
+        if let Err(response) = self.check_can_change(&task_id) {
+            return response;
+        }
+        
+        let Some(task) = self.tasks.get_mut(&task_id) else {
+            return TaskResponse::not_found(&task_id);
+        };
+        let cancelled = task.status == TaskStatus::Cancelled;
+        if !cancelled && !task.archived {
+            return TaskResponse::conflict(Some(task.clone()), "Only cancelled or archived tasks can be restored");
+        }
+        
+        if cancelled {
+            // Deliberately bypasses STATUS_TRANSITIONS, where Cancelled is terminal
+            task.status = TaskStatus::Pending;
+            task.record_event(TaskEventKind::Restored);
+        }
+        if task.archived {
+            task.archived = false;
+            task.record_event(TaskEventKind::Unarchived);
+        }
+        task.mark_modified();
+        let task = task.clone();
+        if cancelled {
+            self.reindex_status(&task.id, &TaskStatus::Cancelled, &task.status);
+        }
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        self.broadcast_stats();
+        hyperware_process_lib::logging::info!("Task {} restored", task.id);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Task restored")
+    }
+    
+    /// Get archived tasks via HTTP endpoint
+    #[http]
+    fn get_archived_tasks(&mut self) -> Vec<Task> {
+        self.record_request("get_archived_tasks");
+        
+        self.tasks
+            .values()
+            .filter(|task| task.archived)
+            .cloned()
+            .collect()
+    }
+    
+    /// Handle local request to get task statistics
+    #[local]
+    fn get_statistics(&mut self) -> TaskManagerStats {
+        self.record_request("get_statistics");
+        
+        TaskManagerStats {
+            total_tasks: self.tasks.len() as u64,
+            pending_tasks: self.status_count(&TaskStatus::Pending),
+            completed_tasks: self.status_count(&TaskStatus::Completed),
+            creation_count: self.task_creation_count,
+            request_count: self.request_count,
+            endpoint_hits: self.endpoint_hits.clone(),
+            ws_connection_count: self.active_ws_connections.len() as u64,
+        }
+    }
+    
+    /// Handle local or HTTP request for per-assignee task counts, over active tasks. A task counts
+    /// once for each of its assignees. Unassigned and cancelled tasks aren't counted, but every
+    /// assignee with a task appears, in name order.
+    #[local]
+    #[http]
+    fn get_assignee_workload(&mut self) -> Vec<(String, AssigneeStats)> {
+        self.record_request("get_assignee_workload");
+        
+        let mut workload: HashMap<String, AssigneeStats> = HashMap::new();
+        for task in self.active_tasks() {
+            for assignee in &task.assignees {
+                let stats = workload.entry(assignee.clone()).or_default();
+                match task.status {
+                    TaskStatus::Pending => stats.pending += 1,
+                    TaskStatus::InProgress => stats.in_progress += 1,
+                    TaskStatus::Completed => stats.completed += 1,
+                    TaskStatus::Cancelled => {}
+                }
+            }
+        }
+        let mut workload: Vec<(String, AssigneeStats)> = workload.into_iter().collect();
+        workload.sort_by(|a, b| a.0.cmp(&b.0));
+        workload
+    }
+    
+    /// Handle local request for completion metrics over tasks created or completed at or after
+    /// `since` (unix seconds), archived ones included. `completion_rate` is completions per
+    /// creation in the window, so it can exceed 1 when older tasks get finished; it's 0 when
+    /// nothing was created.
+    #[local]
+    fn get_completion_metrics(&mut self, since: u64) -> CompletionMetrics {
+        self.record_request("get_completion_metrics");
+        
+        let mut created_in_window = 0;
+        let mut completed_in_window = 0;
+        let mut total_time_to_complete = 0;
+        for task in self.tasks.values() {
+            if task.created_at >= since {
+                created_in_window += 1;
+            }
+            if let Some(completed_at) = task.completion_time().filter(|at| *at >= since) {
+                completed_in_window += 1;
+                total_time_to_complete += completed_at.saturating_sub(task.created_at);
+            }
+        }
+        
+        CompletionMetrics {
+            created_in_window,
+            completed_in_window,
+            completion_rate: if created_in_window == 0 {
+                0.0
+            } else {
+                completed_in_window as f64 / created_in_window as f64
+            },
+            avg_time_to_complete_secs: (completed_in_window > 0)
+                .then(|| total_time_to_complete / completed_in_window),
+        }
+    }
+    
+    /// Handle local request to total estimated and logged effort over active tasks. Remaining
+    /// effort counts open tasks with an estimate, each contributing what's left of its estimate,
+    /// so a task that ran over doesn't offset the others.
+    #[local]
+    fn get_effort_rollup(&mut self) -> EffortRollup {
+        self.record_request("get_effort_rollup");
+        
+        let mut rollup = EffortRollup::default();
+        for task in self.active_tasks() {
+            rollup.task_count += 1;
+            rollup.logged_secs = rollup.logged_secs.saturating_add(task.logged_secs);
+            let Some(estimate_secs) = task.estimate_secs else {
+                rollup.unestimated_tasks += 1;
+                continue;
+            };
+            rollup.estimated_secs = rollup.estimated_secs.saturating_add(estimate_secs);
+            if !matches!(task.status, TaskStatus::Completed | TaskStatus::Cancelled) {
+                let remaining = estimate_secs.saturating_sub(task.logged_secs);
+                rollup.remaining_secs = rollup.remaining_secs.saturating_add(remaining);
+            }
+        }
+        rollup
+    }
+    
+    /// Handle local request to reset the request and creation counters
+    #[local]
+    fn reset_statistics(&mut self) {
+        self.request_count = 0;
+        self.task_creation_count = 0;
+        self.endpoint_hits.clear();
+    }
+    
+    /// Handle local request to verify the status index against a fresh rebuild from `tasks`.
+    /// Used by the test suite to catch index updates missed by a handler.
+    #[local]
+    fn check_status_index(&mut self) -> bool {
+        let consistent = self.status_index == self.build_status_index();
+        if !consistent {
+            hyperware_process_lib::logging::error!("Status index out of sync with tasks");
+        }
+        consistent
+    }
+    
+    /// Handle local request to enable or disable seeding the "Welcome Task" on startup
+    #[local]
+    fn set_seed_welcome_task(&mut self, enabled: bool) {
+        self.record_request("set_seed_welcome_task");
+        self.seed_welcome_task = enabled;
+    }
+    
+    /// Handle local request for the correlation id of the last request that stored a task.
+    /// Used by the test suite to check ids reach the storage call.
+    #[local]
+    fn get_last_storage_correlation_id(&mut self) -> Option<String> {
+        self.last_storage_correlation_id.clone()
+    }
+    
+    /// Handle local request for the names of the events a new, unfiltered subscriber is sent, in
+    /// order. Used by the test suite, which can't open a WebSocket, to check the handshake.
+    #[local]
+    fn get_subscribe_sequence(&mut self) -> Vec<String> {
+        self.subscribe_events(&WsConnection::unfiltered("test".to_string()), None)
+            .iter()
+            .map(|(_, event)| event.name())
+            .collect()
+    }
+    
+    /// Handle local request for the frames a new, unfiltered subscriber resuming from `since_seq`
+    /// of run `run_id` is sent, as JSON text. Used by the test suite, which can't open a
+    /// WebSocket, to check sequence numbers and replay.
+    #[local]
+    fn get_resume_frames(&mut self, run_id: Option<String>, since_seq: Option<u64>) -> Vec<String> {
+        let resume = run_id.zip(since_seq);
+        let resume = resume.as_ref().map(|(run_id, since_seq)| (run_id.as_str(), *since_seq));
+        self.subscribe_events(&WsConnection::unfiltered("test".to_string()), resume)
+            .iter()
+            .filter_map(|(seq, event)| event.encode(WS_PROTOCOL_VERSION, *seq))
+            .filter_map(|frame| String::from_utf8(frame).ok())
+            .collect()
+    }
+    
+    /// Handle local request to subscribe a stand-in WebSocket channel, with no filter, and name
+    /// the events it would be sent. Nothing is actually sent. Used by the test suite, which can't
+    /// open a WebSocket, to check the connection cap; `disconnect_ws_channel` removes the channel.
+    #[local]
+    fn subscribe_test_channel(&mut self, channel_id: u32) -> Vec<String> {
+        let connection = WsConnection::unfiltered(format!("test-{}", channel_id));
+        match self.subscribe(channel_id, connection, None) {
+            Some(events) => events.iter().map(|(_, event)| event.name()).collect(),
+            None => vec![ServerWsEvent::ServerBusy.name()],
+        }
+    }
+    
+    /// Handle local request for the frames an unfiltered subscriber asking for `compression` is
+    /// sent for a snapshot of the active tasks, as JSON text. Used by the test suite, which can't
+    /// open a WebSocket, to check compression.
+    #[local]
+    fn get_snapshot_frames(&mut self, compression: Option<String>) -> Vec<String> {
+        let tasks = self.active_tasks().cloned().collect();
+        snapshot_events(WS_PROTOCOL_VERSION, accepts_gzip(compression.as_deref()), tasks)
+            .iter()
+            .filter_map(|event| serde_json::to_string(event).ok())
+            .collect()
+    }
+    
+    /// Handle local request to drop a WebSocket subscriber and close its channel. Returns false
+    /// if the channel wasn't subscribed.
+    #[local]
+    fn disconnect_ws_channel(&mut self, channel_id: u32) -> bool {
+        self.record_request("disconnect_ws_channel");
+        
+        let subscribed = self.active_ws_connections.contains_key(&channel_id);
+        self.close_ws_channel(channel_id);
+        subscribed
+    }
+    
+    /// Handle local request to cap how many WebSocket clients can be subscribed at once. Zero is
+    /// rejected. Lowering the cap disconnects nobody; new subscriptions are refused until enough
+    /// clients leave.
+    #[local]
+    fn set_max_ws_connections(&mut self, max_ws_connections: usize) -> bool {
+        self.record_request("set_max_ws_connections");
+        
+        if max_ws_connections == 0 {
+            return false;
+        }
+        self.max_ws_connections = max_ws_connections;
+        true
+    }
+    
+    /// Handle local request to set how many attempts each storage call gets. Zero is rejected.
+    /// Task stores always make a single attempt; failed ones are retried by the tick's sweep.
+    #[local]
+    fn set_storage_attempts(&mut self, attempts: u32) -> bool {
+        self.record_request("set_storage_attempts");
+        
+        if attempts == 0 {
+            return false;
+        }
+        self.storage_attempts = attempts;
+        true
+    }
+    
+    /// Handle local request to send tasks to storage packed as MessagePack instead of JSON.
+    /// Enabling asks the storage process whether it supports packed tasks; returns whether they
+    /// are now in use. Storage processes without support keep getting JSON.
+    #[local]
+    async fn set_compact_storage(&mut self, enabled: bool) -> bool {
+        self.record_request("set_compact_storage");
+        self.compact_storage = enabled;
+        self.negotiate_storage_encoding().await;
+        self.storage().packed
+    }
+    
+    /// Handle local request to compare the size of a storage call carrying every task as JSON
+    /// and packed, and check both decode back to the same tasks. Used by the test suite.
+    #[local]
+    fn compare_storage_encodings(&mut self) -> StorageEncodingReport {
+        self.record_request("compare_storage_encodings");
+        
+        let tasks: Vec<Task> = self.tasks.values().cloned().collect();
+        let json_body = json!({"AddTasks": tasks}).to_string();
+        let packed = match pack_tasks(&tasks) {
+            Ok(packed) => packed,
+            Err(e) => {
+                hyperware_process_lib::logging::error!("Failed to pack tasks: {}", e);
+                String::new()
+            }
+        };
+        let packed_body = json!({"AddTasksPacked": packed}).to_string();
+        
+        let from_json = serde_json::from_str::<serde_json::Value>(&json_body)
+            .ok()
+            .and_then(|body| serde_json::from_value::<Vec<Task>>(body["AddTasks"].clone()).ok());
+        let from_packed = unpack_tasks::<Vec<Task>>(&packed).ok();
+        StorageEncodingReport {
+            task_count: tasks.len() as u64,
+            json_bytes: json_body.len() as u64,
+            packed_bytes: packed_body.len() as u64,
+            round_trip_equal: from_json.as_ref() == Some(&tasks) && from_packed.as_ref() == Some(&tasks),
+        }
+    }
+    
+    /// Handle local request to replace the identities allowed to change any task. Identities are
+    /// process addresses only (see `caller_identity`); HTTP callers are anonymous, can't be
+    /// admins and may change only unowned tasks.
+    #[local]
+    fn set_admins(&mut self, admins: Vec<String>) {
+        self.record_request("set_admins");
+        self.admins = admins.into_iter().map(|admin| admin.trim().to_string()).collect();
+    }
+    
+    /// Handle local request to choose how assignees are checked when tasks are created, updated
+    /// or reassigned. Assignees already on tasks are left as they are.
+    #[local]
+    fn set_assignee_format(&mut self, format: AssigneeFormat) {
+        self.record_request("set_assignee_format");
+        self.assignee_format = format;
+    }
+    
+    /// Handle local request to archive Completed tasks once they have been completed for
+    /// `retention_secs`, or `None` to keep them active. Zero is rejected. Tasks are checked on
+    /// every tick.
+    #[local]
+    fn set_completed_retention_secs(&mut self, retention_secs: Option<u64>) -> bool {
+        self.record_request("set_completed_retention_secs");
+        
+        if retention_secs == Some(0) {
+            return false;
+        }
+        self.completed_retention_secs = retention_secs;
+        true
+    }
+    
+    /// Handle local request to run the tick's auto-archive sweep now, returning the ids it
+    /// archived. Used by the test suite, which can't wait for a tick.
+    #[local]
+    async fn run_auto_archive(&mut self) -> Vec<String> {
+        self.record_request("run_auto_archive");
+        self.archive_expired_tasks().await
+    }
+    
+    /// Handle local request to cap how many tasks are kept, or `None` for no cap. Zero is
+    /// rejected. Lowering the cap below the current count evicts nothing until the next create.
+    #[local]
+    fn set_max_tasks(&mut self, max_tasks: Option<usize>) -> bool {
+        self.record_request("set_max_tasks");
+        
+        if max_tasks == Some(0) {
+            return false;
+        }
+        self.max_tasks = max_tasks;
+        true
+    }
+    
+    /// Handle local request to set the token WebSocket clients must subscribe with, or `None` to
+    /// let any client subscribe. An empty token is rejected. Current subscribers are
+    /// disconnected so they have to authenticate against the new setting.
+    #[local]
+    fn set_ws_token(&mut self, token: Option<String>) -> bool {
+        self.record_request("set_ws_token");
+        
+        if token.as_ref().is_some_and(|token| token.is_empty()) {
+            return false;
+        }
+        self.ws_auth_token = token;
+        let channel_ids: Vec<u32> = self.active_ws_connections.keys().copied().collect();
+        for channel_id in channel_ids {
+            self.close_ws_channel(channel_id);
+        }
+        true
+    }
+    
+    /// Handle local request to retry storing queued tasks now rather than on the next tick.
+    /// Returns the ids still waiting to be stored.
+    #[local]
+    async fn sync_pending_storage(&mut self) -> Vec<String> {
+        self.record_request("sync_pending_storage");
+        
+        self.sweep_pending_storage().await;
+        self.pending_storage_sync.clone()
+    }
+    
+    /// Handle local request to write every local task to storage, e.g. after storage was offline
+    /// for a while. Tasks go in batches of STORAGE_SYNC_BATCH_LEN; ones that can't be stored are
+    /// queued for the background sync and listed in the report. Storage upserts, so repeating
+    /// the call is harmless, and local tasks are never changed.
+    #[local]
+    async fn sync_all_to_storage(&mut self) -> SyncReport {
+        self.record_request("sync_all_to_storage");
+        
+        let tasks: Vec<Task> = self.tasks_in_creation_order().into_iter().cloned().collect();
+        // Everything is about to be written, so only this run's failures need queueing
+        self.pending_storage_sync.clear();
+        let mut failed = Vec::new();
+        for batch in tasks.chunks(STORAGE_SYNC_BATCH_LEN) {
+            failed.extend(self.persist_batch(batch).await);
+        }
+        hyperware_process_lib::logging::info!(
+            "Synced {} tasks to storage ({} failures)",
+            tasks.len(),
+            failed.len()
+        );
+        
+        SyncReport {
+            attempted: tasks.len() as u64,
+            succeeded: (tasks.len() - failed.len()) as u64,
+            failed,
+        }
+    }
+    
+    /// Handle local request to compare local tasks with the storage process's copies.
+    /// Only reports differences; nothing is merged.
+    #[local]
+    async fn reconcile_with_storage(&mut self) -> ReconcileReport {
+        self.record_request("reconcile_with_storage");
+        
+        let mut report = ReconcileReport::default();
+        let stored_tasks = match get_stored_tasks(&self.storage()).await {
+            Ok(stored_tasks) => stored_tasks,
+            Err(e) => {
+                log_storage_failure("load tasks for reconciliation", &e);
+                report.storage_error = Some(e.to_string());
+                return report;
+            }
+        };
+        
+        let mut stored_ids = HashSet::new();
+        for stored in stored_tasks {
+            match self.tasks.get(&stored.id) {
+                None => report.only_storage.push(stored.id.clone()),
+                Some(local) if *local != stored => report.conflicting.push(stored.id.clone()),
+                Some(_) => {}
+            }
+            stored_ids.insert(stored.id);
+        }
+        report.only_local = self
+            .tasks
+            .keys()
+            .filter(|id| !stored_ids.contains(*id))
+            .cloned()
+            .collect();
+        
+        report.only_local.sort();
+        report.only_storage.sort();
+        report.conflicting.sort();
+        report
+    }
+    
+    /// Handle local request to get the address of the storage process
+    #[local]
+    fn get_storage_address(&mut self) -> String {
+        self.record_request("get_storage_address");
+        self.storage_address().to_string()
+    }
+    
+    /// Handle local request to point the task manager at a different storage process.
+    /// Returns false, keeping the current address, if `addr` isn't a valid `node@process` address.
+    #[local]
+    fn set_storage_address(&mut self, addr: String) -> bool {
+        self.record_request("set_storage_address");
+        
+        match addr.parse::<Address>() {
+            Ok(address) => {
+                // A different storage process may not take packed tasks; JSON until asked again
+                self.storage_address = Some(address.to_string());
+                self.storage_accepts_packed = false;
+                true
+            }
+            Err(e) => {
+                hyperware_process_lib::logging::warn!("Rejected storage address {:?}: {:?}", addr, e);
+                false
+            }
+        }
+    }
+    
+    /// Handle both local and remote requests to get tasks by status
+    #[local]
+    #[remote]
+    fn get_tasks_by_status(&mut self, status: TaskStatus) -> Vec<Task> {
+        self.record_request("get_tasks_by_status");
+        
+        let Some(ids) = self.status_index.get(&status) else {
+            return Vec::new();
+        };
+        ids.iter()
+            .filter_map(|id| self.tasks.get(id))
+            .filter(|task| !task.archived)
+            .cloned()
+            .collect()
+    }
+    
+    /// Handle both local and remote requests to get non-archived tasks by priority
+    #[local]
+    #[remote]
+    fn get_tasks_by_priority(&mut self, priority: TaskPriority) -> Vec<Task> {
+        self.record_request("get_tasks_by_priority");
+        
+        self.active_tasks()
+            .filter(|task| task.priority == priority)
+            .cloned()
+            .collect()
+    }
+    
+    /// Handle both local and remote requests to get non-archived tasks with a user among their
+    /// assignees. Matching is exact and case-sensitive, since assignees are node/process
+    /// identifiers rather than display names.
+    #[local]
+    #[remote]
+    fn get_tasks_by_assignee(&mut self, assignee: String) -> Vec<Task> {
+        self.record_request("get_tasks_by_assignee");
+        
+        self.active_tasks()
+            .filter(|task| task.assignees.contains(&assignee))
+            .cloned()
+            .collect()
+    }
+    
+    /// Handle both local and remote requests to get non-archived tasks with no assignee
+    #[local]
+    #[remote]
+    fn get_unassigned_tasks(&mut self) -> Vec<Task> {
+        self.record_request("get_unassigned_tasks");
+        
+        self.active_tasks()
+            .filter(|task| task.assignees.is_empty())
+            .cloned()
+            .collect()
+    }
+    
+    /// Persist state immediately instead of waiting for the next `EveryNMessage` save,
+    /// e.g. after a bulk import or before a planned shutdown.
+    ///
+    /// app-common only saves from its own message loop and has no manual save hook, so this
+    /// writes the state directly using the same encoding the framework restores from. A public
+    /// `hyperware_app_common::save_state(&state)` would let us drop the duplicated encoding.
+    #[local]
+    async fn flush_state(&mut self) -> bool {
+        self.record_request("flush_state");
+        self.save_state_now()
+    }
+    
+    /// Handle local debug request to read back the last saved state, decoded the way the
+    /// framework restores it on startup. Returns the saved task ids, sorted, or None if nothing
+    /// is saved or it doesn't decode.
+    #[local]
+    fn get_saved_task_ids(&mut self) -> Option<Vec<String>> {
+        self.record_request("get_saved_task_ids");
+        
+        let state_bytes = hyperware_process_lib::get_state()?;
+        let saved: TaskManagerState = rmp_serde::from_slice(&state_bytes).ok()?;
+        let mut ids: Vec<String> = saved.tasks.into_keys().collect();
+        ids.sort();
+        Some(ids)
+    }
+    
+    /// Admin call to run before a planned restart: flushes state and tells WebSocket clients
+    /// the server is going away so they can reconnect once it's back.
+    ///
+    /// hyperprocess has no shutdown or terminate hook (only `#[init]`, `#[http]`, `#[local]`,
+    /// `#[remote]` and `#[ws]` handlers), so an unplanned stop still loses any changes made
+    /// since the last `EveryNMessage` save. A framework hook could simply call this.
+    #[local]
+    async fn prepare_shutdown(&mut self) -> bool {
+        self.record_request("prepare_shutdown");
+        
+        let saved = self.save_state_now();
+        self.broadcast_event(&ServerWsEvent::ServerShuttingDown);
+        saved
+    }
+    
+    /// Handle a remote notification that a task on another node was assigned to this node,
+    /// forwarding it to our WebSocket clients
+    #[remote]
+    fn notify_assignment(&mut self, task: Task) -> bool {
+        self.record_request("notify_assignment");
+        
+        hyperware_process_lib::logging::info!("Task {} was assigned to us", task.id);
+        self.broadcast_event(&ServerWsEvent::TaskAssigned(task));
+        true
+    }
+    
+    /// Handle a remote notification from the storage process that a task changed outside this
+    /// process. The task is applied if it's new or its version is newer than ours, then
+    /// broadcast; it isn't stored again, since storage already has it. Returns whether it was
+    /// applied: stale versions and invalid tasks are ignored.
+    #[remote]
+    fn on_external_task_change(&mut self, task: Task) -> bool {
+        self.record_request("on_external_task_change");
+        
+        if let Some(local) = self.tasks.get(&task.id) {
+            if task.version <= local.version {
+                return false;
+            }
+        }
+        if let Err(reason) = validate_text(&task.title, &task.description)
+            .and_then(|()| validate_metadata(&task.metadata))
+        {
+            hyperware_process_lib::logging::warn!(
+                "Ignored external change to task {}: {}",
+                task.id,
+                reason
+            );
+            return false;
+        }
+        
+        // A local change still waiting to be stored is superseded by the newer stored version
+        self.pending_storage_sync.retain(|id| id != &task.id);
+        let mut task = task;
+        task.backfill_defaults();
+        self.insert_task(task.clone());
+        self.broadcast_task_update(&task);
+        self.broadcast_stats();
+        hyperware_process_lib::logging::info!(
+            "Applied external change to task {} at version {}",
+            task.id,
+            task.version
+        );
+        true
+    }
+    
+    /// Periodic maintenance, driven by a self-scheduled timer started in `initialize`. Only
+    /// accepted from ourselves: each accepted tick re-arms the timer, so a tick from anyone else
+    /// would start a second maintenance loop that never stops.
+    #[local]
+    async fn tick(&mut self) {
+        if hyperware_app_common::source() != hyperware_process_lib::our() {
+            hyperware_process_lib::logging::warn!("Ignored tick from {}", hyperware_app_common::source());
+            return;
+        }
+        
+        // Storage was unreachable at startup; try loading again
+        if self.storage_load_pending {
+            self.load_stored_tasks().await;
+        }
+        if !self.pending_storage_sync.is_empty() {
+            self.sweep_pending_storage().await;
+        }
+        
+        self.flag_overdue_tasks();
+        self.evict_stale_connections();
+        self.archive_expired_tasks().await;
+        
+        sleep_secs(TICK_INTERVAL_SECS).await;
+        request_tick();
+    }
+    
+    /// Handle WebSocket messages for real-time updates
+    #[ws]
+    fn handle_websocket(&mut self, channel_id: u32, message_type: WsMessageType, blob: LazyLoadBlob) {
+        // Any frame from a subscribed client shows it's still alive
+        if let Some(connection) = self.active_ws_connections.get_mut(&channel_id) {
+            connection.last_seen = now_secs();
+        }
+        
+        match message_type {
+            WsMessageType::Binary | WsMessageType::Text => {
+                if !self.allow_ws_message(channel_id) {
+                    return;
+                }
+                
+                // Clients send JSON either as binary frames or, like a browser's
+                // `ws.send(JSON.stringify(...))`, as UTF-8 text frames
+                match serde_json::from_slice::<WebSocketMessage>(blob.bytes()) {
+                    Ok(ws_message) => match ws_message {
+                        WebSocketMessage::Subscribe {
+                            client_id,
+                            version,
+                            filter,
+                            stats,
+                            auth_token,
+                            compression,
+                            since_seq,
+                            run_id,
+                        } => {
+                            if !self.ws_token_accepted(&auth_token) {
+                                // No data for unauthenticated clients; tell them why, then hang up
+                                self.send_ws_notice(channel_id, &ServerWsEvent::Unauthorized);
+                                self.close_ws_channel(channel_id);
+                                return;
+                            }
+                            
+                            // Register client for updates, remembering which message format it
+                            // speaks and which tasks it wants to hear about
+                            let connection = WsConnection {
+                                client_id,
+                                protocol_version: version,
+                                last_seen: now_secs(),
+                                filter,
+                                stats,
+                                gzip: accepts_gzip(compression.as_deref()),
+                            };
+                            let resume = run_id.zip(since_seq);
+                            let resume = resume.as_ref().map(|(run_id, since_seq)| (run_id.as_str(), *since_seq));
+                            let Some(events) = self.subscribe(channel_id, connection, resume) else {
+                                // Over the connection cap; tell the client why, then hang up
+                                self.send_ws_notice(channel_id, &ServerWsEvent::ServerBusy);
+                                self.close_ws_channel(channel_id);
+                                return;
+                            };
+                            for (seq, event) in events {
+                                self.send_ws_event(channel_id, seq, &event);
+                            }
+                        }
+                        WebSocketMessage::Unsubscribe => {
+                            // Remove client subscription
+                            self.active_ws_connections.remove(&channel_id);
+                        }
+                        WebSocketMessage::Query { status } => {
+                            // Answer with the requested slice, independent of the subscription filter
+                            if self.active_ws_connections.contains_key(&channel_id) {
+                                let tasks = self
+                                    .active_tasks()
+                                    .filter(|task| status.as_ref().is_none_or(|status| task.status == *status))
+                                    .cloned()
+                                    .collect();
+                                self.send_snapshot_tasks(channel_id, tasks);
+                            } else {
+                                self.send_ws_error(channel_id, "Subscribe before querying".to_string());
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        // Tell the client what was wrong with its payload rather than dropping it
+                        self.send_ws_error(channel_id, format!("Invalid message: {}", e));
+                    }
+                }
+            }
+            WsMessageType::Ping => {
+                // Answer heartbeats so clients can detect a dead server too
+                if let Some(server) = hyperware_app_common::get_server() {
+                    let _ = server.send_ws_message(channel_id, WsMessageType::Pong, Vec::new());
+                }
+            }
+            WsMessageType::Close => {
+                // Client disconnected, remove from active connections
+                self.active_ws_connections.remove(&channel_id);
+                self.ws_rate.remove(&channel_id);
+            }
+            _ => { /* Ignore other message types */ }
+        }
+    }
+    
+    // Helper method to build the tasks for a bulk creation, setting aside invalid requests
+    // with their index and reason. Doesn't touch state.
+    fn prepare_bulk(&self, reqs: Vec<NewTaskRequest>) -> BulkCreateResponse {
+        let text_bytes = reqs.iter().map(NewTaskRequest::text_bytes).sum();
+        if let Err(reason) = check_batch_limits(reqs.len(), text_bytes) {
+            return BulkCreateResponse { created: Vec::new(), failed: vec![reason] };
+        }
+        
+        let owner = caller_identity();
+        let mut created = Vec::new();
+        let mut failed = Vec::new();
+        for (index, req) in reqs.into_iter().enumerate() {
+            let checked = req
+                .validate()
+                .and_then(|()| self.check_parent(&req))
+                .and_then(|()| self.assignee_format.check(&req.assignees));
+            match checked {
+                Ok(()) => created.push(Task { owner: owner.clone(), ..Task::from_request(req) }),
+                Err(reason) => failed.push(format!("#{}: {}", index, reason)),
+            }
+        }
+        BulkCreateResponse { created, failed }
+    }
+    
+    // Helper method to validate a creation request, then store, persist and broadcast its task
+    async fn create_from_request(&mut self, mut new_task_req: NewTaskRequest) -> TaskResponse {
+        let correlation_id = correlation_id_or_new(new_task_req.correlation_id.take());
+        
+        // A retried request gets the task its first attempt created
+        self.prune_idempotency_keys();
+        if let Some(key) = &new_task_req.idempotency_key {
+            if let Some(task) = self.idempotency_keys.get(key).and_then(|id| self.tasks.get(id)) {
+                let response = TaskResponse::ok(
+                    task.clone(),
+                    true,
+                    "Duplicate request; returning the task already created for this idempotency key",
+                );
+                return response.with_correlation_id(correlation_id);
+            }
+        }
+        
+        if let Err(message) = new_task_req
+            .validate()
+            .and_then(|()| self.check_parent(&new_task_req))
+            .and_then(|()| self.assignee_format.check(&new_task_req.assignees))
+        {
+            return TaskResponse::bad_request(message).with_correlation_id(correlation_id);
+        }
+        
+        // Generate new task with UUID
+        let idempotency_key = new_task_req.idempotency_key.clone();
+        let task = Task { owner: caller_identity(), ..Task::from_request(new_task_req) };
+        if let Err(message) = self.make_room(std::slice::from_ref(&task)).await {
+            return TaskResponse::conflict(None, message).with_correlation_id(correlation_id);
+        }
+        
+        // Store task locally
+        self.insert_task(task.clone());
+        if let Some(key) = idempotency_key {
+            self.idempotency_keys.insert(key, task.id.clone());
+        }
+        self.task_creation_count += 1;
+        
+        // Asynchronously store in the persistent storage process
+        let storage_result = self.persist_task_traced(&task, &correlation_id).await;
+        
+        // Notify connected WebSocket clients about the new task
+        self.broadcast_task_update(&task);
+        self.broadcast_stats();
+        notify_assignees(&task, &task.assignees);
+        hyperware_process_lib::logging::info!("Created task {} [{}]", task.id, correlation_id);
+        
+        // Return response with task info and storage status
+        TaskResponse::ok(task, storage_result.is_ok(), "Task created successfully")
+            .with_correlation_id(correlation_id)
+    }
+    
+    // Helper method to keep the task count within `max_tasks` once `incoming` is added, by
+    // evicting the oldest Completed and Cancelled tasks. Tasks that are a parent, or about to
+    // be one, are kept like `delete_task` would. When too few tasks can be evicted, nothing
+    // is and the creation must be rejected.
+    async fn make_room(&mut self, incoming: &[Task]) -> Result<(), String> {
+        let Some(max_tasks) = self.max_tasks else {
+            return Ok(());
+        };
+        let needed = (self.tasks.len() + incoming.len()).saturating_sub(max_tasks);
+        if needed == 0 {
+            return Ok(());
+        }
+        
+        let parents: HashSet<&str> = self
+            .tasks
+            .values()
+            .chain(incoming)
+            .filter_map(|task| task.parent_id.as_deref())
+            .collect();
+        let mut evictable: Vec<&Task> = self
+            .tasks
+            .values()
+            .filter(|task| matches!(task.status, TaskStatus::Completed | TaskStatus::Cancelled))
+            .filter(|task| !parents.contains(task.id.as_str()))
+            .collect();
+        if evictable.len() < needed {
+            return Err(format!(
+                "Task limit of {} reached and only {} finished tasks can be evicted; \
+                 complete or delete tasks first",
+                max_tasks,
+                evictable.len()
+            ));
+        }
+        evictable.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        let evicted: Vec<String> = evictable.into_iter().take(needed).map(|task| task.id.clone()).collect();
+        
+        let storage = self.storage();
+        for task_id in &evicted {
+            self.remove_task(task_id);
+            self.record_tombstone(task_id);
+            if let Err(e) = delete_task_in_storage(&storage, task_id).await {
+                log_storage_failure(&format!("delete evicted task {}", task_id), &e);
+            }
+            self.broadcast_task_deletion(task_id);
+            hyperware_process_lib::logging::info!(
+                "Evicted task {} to stay within the limit of {} tasks",
+                task_id,
+                max_tasks
+            );
+        }
+        Ok(())
+    }
+    
+    // Helper method to persist new tasks in one storage call. If that fails, falls back to
+    // individual writes and returns reasons for just the tasks that really couldn't be stored.
+    async fn persist_batch(&mut self, tasks: &[Task]) -> Vec<String> {
+        let mut failed = Vec::new();
+        if tasks.is_empty() {
+            return failed;
+        }
+        let storage = self.storage();
+        if let Err(e) = store_tasks_in_storage(&storage, tasks).await {
+            let operation = format!("store batch of {} tasks; retrying one at a time", tasks.len());
+            log_storage_failure(&operation, &e);
+            let mut last_error = None;
+            for task in tasks {
+                if let Err(e) = store_task_in_storage(&storage, task).await {
+                    self.queue_storage_sync(&task.id);
+                    failed.push(format!("{}: failed to persist to storage", task.id));
+                    last_error = Some(e);
+                }
+            }
+            if let Some(e) = last_error {
+                let operation = format!("store {} of {} tasks; queued for sync", failed.len(), tasks.len());
+                log_storage_failure(&operation, &e);
+            }
+        }
+        failed
+    }
+    
+    // Helper method to forget idempotency keys whose task is gone or older than
+    // IDEMPOTENCY_KEY_TTL_SECS, then the oldest keys beyond MAX_IDEMPOTENCY_KEYS
+    fn prune_idempotency_keys(&mut self) {
+        let cutoff = now_secs().saturating_sub(IDEMPOTENCY_KEY_TTL_SECS);
+        let tasks = &self.tasks;
+        self.idempotency_keys
+            .retain(|_, task_id| tasks.get(task_id).is_some_and(|task| task.created_at >= cutoff));
+        
+        if self.idempotency_keys.len() > MAX_IDEMPOTENCY_KEYS {
+            let mut by_age: Vec<(u64, String)> = self
+                .idempotency_keys
+                .iter()
+                .map(|(key, task_id)| (tasks[task_id].created_at, key.clone()))
+                .collect();
+            by_age.sort();
+            let excess = self.idempotency_keys.len() - MAX_IDEMPOTENCY_KEYS;
+            for (_, key) in by_age.into_iter().take(excess) {
+                self.idempotency_keys.remove(&key);
+            }
+        }
+    }
+    
+    // Helper method to write the state right away, in the encoding the framework restores from
+    fn save_state_now(&self) -> bool {
+        match rmp_serde::to_vec(self) {
+            Ok(state_bytes) => {
+                hyperware_process_lib::set_state(&state_bytes);
+                true
+            }
+            Err(e) => {
+                hyperware_process_lib::logging::error!("Failed to serialize state for flush: {}", e);
+                false
+            }
+        }
+    }
+    
+    // Helper method to count a request, both overall and for the handling endpoint
+    fn record_request(&mut self, endpoint: &str) {
+        self.request_count += 1;
+        *self.endpoint_hits.entry(endpoint.to_string()).or_insert(0) += 1;
+    }
+    
+    // Helper method checking that a new task's parent, if any, exists
+    fn check_parent(&self, req: &NewTaskRequest) -> Result<(), String> {
+        match &req.parent_id {
+            Some(parent_id) if !self.tasks.contains_key(parent_id) => {
+                Err(format!("Parent task {} not found", parent_id))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    // Helper method checking that the caller may change `task_id`, as its owner or an admin (see
+    // `Task::check_owner`). Err holds the forbidden reply; unknown ids pass, so handlers report
+    // them as usual.
+    fn check_can_change(&self, task_id: &str) -> Result<(), TaskResponse> {
+        let Some(task) = self.tasks.get(task_id) else {
+            return Ok(());
+        };
+        task.check_owner(caller_identity().as_deref(), &self.admins)
+            .map_err(|message| TaskResponse::forbidden(task.clone(), message))
+    }
+    
+    // Helper method checking that `task_id` can be moved under `parent_id`
+    // without becoming its own ancestor
+    fn check_reparent(&self, task_id: &str, parent_id: &str) -> Result<(), String> {
+        let mut ancestor = Some(parent_id);
+        while let Some(id) = ancestor {
+            if id == task_id {
+                return Err("A task cannot be its own ancestor".to_string());
+            }
+            ancestor = match self.tasks.get(id) {
+                Some(task) => task.parent_id.as_deref(),
+                None if id == parent_id => return Err(format!("Parent task {} not found", parent_id)),
+                None => None,
+            };
+        }
+        Ok(())
+    }
+    
+    // Helper method checking that `task_id` may depend on `blocker_id`: the blocker exists and
+    // doesn't already depend on the task, directly or through other tasks
+    fn check_dependency(&self, task_id: &str, blocker_id: &str) -> Result<(), String> {
+        if task_id == blocker_id {
+            return Err("A task cannot depend on itself".to_string());
+        }
+        if !self.tasks.contains_key(blocker_id) {
+            return Err(format!("Blocker task {} not found", blocker_id));
+        }
+        let mut stack = vec![blocker_id];
+        let mut visited = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == task_id {
+                return Err(format!("Task {} already depends on {}; this would form a cycle", blocker_id, task_id));
+            }
+            if visited.insert(id) {
+                if let Some(task) = self.tasks.get(id) {
+                    stack.extend(task.blocked_by.iter().map(String::as_str));
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    // Helper method listing a task's blockers that exist and aren't Completed yet
+    fn unmet_blockers(&self, task_id: &str) -> Vec<String> {
+        let Some(task) = self.tasks.get(task_id) else {
+            return Vec::new();
+        };
+        task.blocked_by
+            .iter()
+            .filter(|id| self.tasks.get(*id).is_some_and(|blocker| blocker.status != TaskStatus::Completed))
+            .cloned()
+            .collect()
+    }
+    
+    // Helper method iterating over tasks that haven't been archived
+    fn active_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.values().filter(|task| !task.archived)
+    }
+    
+    // Helper method counting the active tasks per status
+    fn task_counts(&self) -> TaskCounts {
+        let mut counts = TaskCounts::default();
+        for task in self.active_tasks() {
+            counts.total += 1;
+            match task.status {
+                TaskStatus::Pending => counts.pending += 1,
+                TaskStatus::InProgress => counts.in_progress += 1,
+                TaskStatus::Completed => counts.completed += 1,
+                TaskStatus::Cancelled => counts.cancelled += 1,
+            }
+        }
+        counts
+    }
+    
+    // Helper method returning tasks in a stable order (created_at, then id),
+    // since HashMap iteration order is not deterministic
+    fn tasks_in_creation_order(&self) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.values().collect();
+        tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        tasks
+    }
+    
+    // Helper method to pick a manual order value between `target_id` and the task before it,
+    // ignoring `task_id` itself. None when the two are too close for a value to fit between.
+    fn sort_order_before(&self, task_id: &str, target_id: &str) -> Option<f64> {
+        let target = self.tasks.get(target_id)?;
+        let previous = self
+            .tasks
+            .values()
+            .filter(|task| task.id != task_id && manual_order(task, target).is_lt())
+            .max_by(|a, b| manual_order(a, b));
+        let Some(previous) = previous else {
+            return Some(target.sort_order - 1.0);
+        };
+        let midpoint = previous.sort_order + (target.sort_order - previous.sort_order) / 2.0;
+        (midpoint > previous.sort_order && midpoint < target.sort_order).then_some(midpoint)
+    }
+    
+    // Helper method to respace the manual order as 1.0, 2.0, ... keeping the current order.
+    // Every task changes, so they're persisted together and clients get a fresh snapshot.
+    async fn renumber_sort_order(&mut self) {
+        let mut ordered: Vec<Task> = self.tasks.values().cloned().collect();
+        ordered.sort_by(manual_order);
+        for (index, task) in ordered.iter_mut().enumerate() {
+            task.sort_order = (index + 1) as f64;
+            task.mark_modified();
+        }
+        for task in &ordered {
+            self.insert_task(task.clone());
+        }
+        let failed = self.persist_batch(&ordered).await;
+        hyperware_process_lib::logging::info!(
+            "Renumbered manual order of {} tasks ({} failures)",
+            ordered.len(),
+            failed.len()
+        );
+        self.broadcast_snapshot();
+    }
+    
+    // Helper method to give a task a new place in the manual order and persist and broadcast it
+    async fn move_task_to(&mut self, task_id: &str, sort_order: f64) -> TaskResponse {
+        let Some(task) = self.tasks.get_mut(task_id) else {
+            return TaskResponse::not_found(task_id);
+        };
+        task.sort_order = sort_order;
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        self.broadcast_task_update(&task);
+        
+        TaskResponse::ok(task, storage_result.is_ok(), "Task moved")
+    }
+    
+    // Helper method to add or replace a task, keeping the status index in step
+    fn insert_task(&mut self, mut task: Task) {
+        self.bump_epoch();
+        task.backfill_defaults();
+        if let Some(previous_status) = self.tasks.get(&task.id).map(|t| t.status.clone()) {
+            self.unindex_status(&task.id, &previous_status);
+        }
+        self.status_index
+            .entry(task.status.clone())
+            .or_default()
+            .insert(task.id.clone());
+        self.tasks.insert(task.id.clone(), task);
+    }
+    
+    // Helper method to remove a task along with its status index entry
+    fn remove_task(&mut self, task_id: &str) -> Option<Task> {
+        let task = self.tasks.remove(task_id)?;
+        self.unindex_status(&task.id, &task.status);
+        self.bump_epoch();
+        Some(task)
+    }
+    
+    // Helper method to remember a deleted task for sync clients, pruning tombstones past the
+    // retention window and then the oldest beyond MAX_TOMBSTONES
+    fn record_tombstone(&mut self, task_id: &str) {
+        let now = now_secs();
+        self.tombstones.push(Tombstone { id: task_id.to_string(), deleted_at: now });
+        
+        let cutoff = now.saturating_sub(TOMBSTONE_RETENTION_SECS);
+        self.tombstones.retain(|tombstone| tombstone.deleted_at >= cutoff);
+        if self.tombstones.len() > MAX_TOMBSTONES {
+            let excess = self.tombstones.len() - MAX_TOMBSTONES;
+            self.tombstones.drain(..excess);
+        }
+    }
+    
+    // Helper method to note that `tasks` changed. Tasks changed in place are bumped for when
+    // they're persisted, which every handler does right after the change.
+    fn bump_epoch(&mut self) {
+        self.state_epoch += 1;
+    }
+    
+    // Helper method to move a task between status index entries after its status changed in place
+    fn reindex_status(&mut self, task_id: &str, from: &TaskStatus, to: &TaskStatus) {
+        if from != to {
+            self.unindex_status(task_id, from);
+            self.status_index
+                .entry(to.clone())
+                .or_default()
+                .insert(task_id.to_string());
+        }
+    }
+    
+    // Helper method to drop a task from a status index entry, removing the entry once empty
+    fn unindex_status(&mut self, task_id: &str, status: &TaskStatus) {
+        if let Some(ids) = self.status_index.get_mut(status) {
+            ids.remove(task_id);
+            if ids.is_empty() {
+                self.status_index.remove(status);
+            }
+        }
+    }
+    
+    // Helper method to derive the status index from scratch
+    fn build_status_index(&self) -> HashMap<TaskStatus, HashSet<String>> {
+        let mut index: HashMap<TaskStatus, HashSet<String>> = HashMap::new();
+        for task in self.tasks.values() {
+            index
+                .entry(task.status.clone())
+                .or_default()
+                .insert(task.id.clone());
+        }
+        index
+    }
+    
+    // Helper method to replace the status index with one derived from `tasks`
+    fn rebuild_status_index(&mut self) {
+        self.status_index = self.build_status_index();
+    }
+    
+    // Helper method returning how many tasks, archived included, are in a status
+    fn status_count(&self, status: &TaskStatus) -> u64 {
+        self.status_index.get(status).map_or(0, |ids| ids.len() as u64)
+    }
+    
+    // Helper method to resolve the configured storage process, falling back to the default
+    fn storage_address(&self) -> Address {
+        self.storage_address
+            .as_deref()
+            .and_then(|addr| addr.parse().ok())
+            .unwrap_or_else(|| Address::process(DEFAULT_STORAGE_PROCESS))
+    }
+    
+    // Helper method bundling the storage address with the configured attempts and encoding
+    fn storage(&self) -> StorageTarget {
+        StorageTarget {
+            address: self.storage_address(),
+            attempts: self.storage_attempts,
+            packed: self.compact_storage && self.storage_accepts_packed,
+        }
+    }
+    
+    // Helper method like `storage` with a single attempt, for task stores: a reply may be
+    // waiting on them, and failures are queued for the tick's sweep instead of retried inline
+    fn storage_once(&self) -> StorageTarget {
+        StorageTarget { attempts: 1, ..self.storage() }
+    }
+    
+    // Helper method to ask the storage process whether it takes packed tasks. Only done when
+    // compact storage is enabled, so storage processes that don't know the question aren't
+    // kept waiting on otherwise.
+    async fn negotiate_storage_encoding(&mut self) {
+        self.storage_accepts_packed = self.compact_storage && storage_accepts_packed(&self.storage_address()).await;
+        if self.compact_storage {
+            let encoding = if self.storage_accepts_packed { "packed MessagePack" } else { "JSON" };
+            hyperware_process_lib::logging::info!("Sending tasks to storage as {}", encoding);
+        }
+    }
+    
+    // Helper method to store a task with one attempt, queueing it for the background sync on failure
+    async fn persist_task(&mut self, task: &Task) -> Result<bool, StorageError> {
+        self.bump_epoch();
+        let result = store_task_in_storage(&self.storage_once(), task).await;
+        if let Err(e) = &result {
+            log_storage_failure(&format!("store task {}; queued for sync", task.id), e);
+            self.queue_storage_sync(&task.id);
+        }
+        result
+    }
+    
+    // Helper method like `persist_task` for a change made by a traced request. The storage
+    // protocol has no field for the correlation id, so it's logged with the call instead;
+    // storage's own log lines can be matched up by task id and version.
+    async fn persist_task_traced(&mut self, task: &Task, correlation_id: &str) -> Result<bool, StorageError> {
+        self.bump_epoch();
+        self.last_storage_correlation_id = Some(correlation_id.to_string());
+        hyperware_process_lib::logging::info!(
+            "Storing task {} at version {} [{}]",
+            task.id,
+            task.version,
+            correlation_id
+        );
+        let result = store_task_in_storage(&self.storage_once(), task).await;
+        if let Err(e) = &result {
+            let operation = format!("store task {} [{}]; queued for sync", task.id, correlation_id);
+            log_storage_failure(&operation, e);
+            self.queue_storage_sync(&task.id);
+        }
+        result
+    }
+    
+    // Helper method to remember a task that still needs storing, once
+    fn queue_storage_sync(&mut self, task_id: &str) {
+        if !self.pending_storage_sync.iter().any(|id| id == task_id) {
+            self.pending_storage_sync.push(task_id.to_string());
+        }
+    }
+    
+    // Helper method to store the current version of each queued task with a single attempt
+    // each. Tasks deleted in the meantime are dropped; failures stay queued for the next sweep.
+    async fn sweep_pending_storage(&mut self) {
+        let pending = std::mem::take(&mut self.pending_storage_sync);
+        let storage = self.storage_once();
+        let mut last_error = None;
+        for task_id in pending {
+            let Some(task) = self.tasks.get(&task_id).cloned() else {
+                continue;
+            };
+            if let Err(e) = store_task_in_storage(&storage, &task).await {
+                self.queue_storage_sync(&task_id);
+                last_error = Some(e);
+            }
+        }
+        if let Some(e) = last_error {
+            let still_pending = self.pending_storage_sync.len();
+            log_storage_failure(&format!("sync {} pending tasks", still_pending), &e);
+        }
+    }
+    
+    // Helper method to archive or unarchive a task for its owner or an admin, persisting and
+    // broadcasting the change
+    async fn set_archived(&mut self, task_id: &str, archived: bool) -> TaskResponse {
+        if let Some(task) = self.tasks.get(task_id) {
+            if let Err(message) = task.check_owner(caller_identity().as_deref(), &self.admins) {
+                return TaskResponse::forbidden(task.clone(), message);
+            }
+        }
+        if archived
+            && self
+                .active_tasks()
+                .any(|task| task.parent_id.as_deref() == Some(task_id))
+        {
+            return TaskResponse::conflict(
+                self.tasks.get(task_id).cloned(),
+                "Task has unarchived subtasks; archive them first",
+            );
+        }
+        
+        let Some(task) = self.tasks.get_mut(task_id) else {
+            return TaskResponse::not_found(task_id);
+        };
+        
+        if task.archived == archived {
+            let state = if archived { "archived" } else { "unarchived" };
+            return TaskResponse::ok(task.clone(), true, format!("Task is already {}", state));
+        }
+        task.archived = archived;
+        task.record_event(if archived { TaskEventKind::Archived } else { TaskEventKind::Unarchived });
+        task.mark_modified();
+        let task = task.clone();
+        
+        let storage_result = self.persist_task(&task).await;
+        if archived {
+            self.broadcast_event(&ServerWsEvent::TaskArchived { id: task.id.clone() });
+        } else {
+            self.broadcast_task_update(&task);
+        }
+        self.broadcast_stats();
+        
+        let state = if archived { "archived" } else { "unarchived" };
+        TaskResponse::ok(task, storage_result.is_ok(), format!("Task {}", state))
+    }
+    
+    // Helper method to create the next occurrence of a recurring task once it's completed,
+    // persisting and broadcasting it. Callers skip tasks that were already completed so
+    // repeating the completion can't regenerate the task again.
+    async fn spawn_next_occurrence(&mut self, task: &Task) {
+        let Some(next) = self.start_next_occurrence(task) else {
+            return;
+        };
+        
+        // persist_task logs and queues a failed store
+        let _ = self.persist_task(&next).await;
+        self.broadcast_task_update(&next);
+    }
+    
+    // Helper method to add the next occurrence of a just-completed recurring task, leaving
+    // storing and broadcasting it to the caller
+    fn start_next_occurrence(&mut self, task: &Task) -> Option<Task> {
+        if task.status != TaskStatus::Completed {
+            return None;
+        }
+        let next = task.next_occurrence(now_secs())?;
+        
+        self.insert_task(next.clone());
+        self.task_creation_count += 1;
+        notify_assignees(&next, &next.assignees);
+        hyperware_process_lib::logging::info!(
+            "Created task {} as the next occurrence of {}",
+            next.id,
+            task.id
+        );
+        Some(next)
+    }
+    
+    // Helper method to merge tasks from the storage process into local state.
+    // Tasks already held locally take precedence over their stored copies.
+    async fn load_stored_tasks(&mut self) {
+        match get_stored_tasks(&self.storage()).await {
+            Ok(stored_tasks) => {
+                let loaded = stored_tasks.len();
+                for task in stored_tasks {
+                    if !self.tasks.contains_key(&task.id) {
+                        self.insert_task(task);
+                    }
+                }
+                self.storage_load_pending = false;
+                hyperware_process_lib::logging::info!("Loaded {} tasks from storage", loaded);
+            }
+            Err(StorageError::Deserialize(e)) => {
+                // Retrying won't fix a malformed reply
+                self.storage_load_pending = false;
+                hyperware_process_lib::logging::error!("Storage returned unreadable tasks: {}", e);
+            }
+            Err(e) => {
+                self.storage_load_pending = true;
+                hyperware_process_lib::logging::warn!("Storage unavailable ({}), will retry loading tasks later", e);
+            }
+        }
+    }
+    
+    // Helper method to archive the Completed tasks whose `completed_at` is more than
+    // `completed_retention_secs` ago, storing them in one call. Tasks completed before
+    // `completed_at` was tracked are left alone, as are tasks with unarchived subtasks.
+    async fn archive_expired_tasks(&mut self) -> Vec<String> {
+        let Some(retention_secs) = self.completed_retention_secs else {
+            return Vec::new();
+        };
+        let cutoff = now_secs().saturating_sub(retention_secs);
+        let parents: HashSet<String> = self.active_tasks().filter_map(|task| task.parent_id.clone()).collect();
+        let expired: Vec<String> = self
+            .active_tasks()
+            .filter(|task| task.status == TaskStatus::Completed && !parents.contains(&task.id))
+            .filter(|task| task.completed_at.is_some_and(|completed_at| completed_at < cutoff))
+            .map(|task| task.id.clone())
+            .collect();
+        if expired.is_empty() {
+            return expired;
+        }
+        
+        let mut archived = Vec::new();
+        for id in &expired {
+            if let Some(task) = self.tasks.get_mut(id) {
+                task.archived = true;
+                task.record_event(TaskEventKind::Archived);
+                task.mark_modified();
+                archived.push(task.clone());
+            }
+        }
+        self.bump_epoch();
+        self.persist_batch(&archived).await;
+        for id in &expired {
+            self.broadcast_event(&ServerWsEvent::TaskArchived { id: id.clone() });
+        }
+        self.broadcast_stats();
+        hyperware_process_lib::logging::info!(
+            "Auto-archived {} tasks completed over {}s ago",
+            expired.len(),
+            retention_secs
+        );
+        expired
+    }
+    
+    // Helper method to broadcast a `TaskOverdue` event exactly once per task that has become overdue
+    fn flag_overdue_tasks(&mut self) {
+        let now = now_secs();
+        let newly_overdue: Vec<String> = self
+            .tasks
+            .values()
+            .filter(|task| task.is_overdue(now) && !self.notified_overdue.contains(&task.id))
+            .map(|task| task.id.clone())
+            .collect();
+        
+        for id in newly_overdue {
+            self.broadcast_event(&ServerWsEvent::TaskOverdue { id: id.clone() });
+            self.notified_overdue.insert(id);
+        }
+        
+        // Forget tasks that are no longer overdue (closed, rescheduled or deleted) so they can be flagged again
+        self.notified_overdue
+            .retain(|id| self.tasks.get(id).is_some_and(|task| task.is_overdue(now)));
+    }
+    
+    // Helper method to send an event, stamped with sequence number `seq`, to a single subscribed
+    // WebSocket channel
+    fn send_ws_event(&mut self, channel_id: u32, seq: u64, event: &ServerWsEvent) {
+        let Some(server) = hyperware_app_common::get_server() else {
+            return;
+        };
+        if let Some(connection) = self.active_ws_connections.get(&channel_id) {
+            if let Some(payload) = event.encode(connection.protocol_version, seq) {
+                if server.send_ws_message(channel_id, WsMessageType::Binary, payload).is_err() {
+                    self.active_ws_connections.remove(&channel_id);
+                }
+            }
+        }
+    }
+    
+    // Helper method to report a problem to a WebSocket client. Sent whether or not the channel
+    // is subscribed; see `send_ws_notice` for the format.
+    fn send_ws_error(&self, channel_id: u32, message: String) {
+        self.send_ws_notice(channel_id, &ServerWsEvent::Error { message });
+    }
+    
+    // Helper method to check a subscriber's token against the configured one, if any. Compares in
+    // constant time so response timing doesn't reveal how much of a guess was right.
+    fn ws_token_accepted(&self, presented: &str) -> bool {
+        let Some(expected) = &self.ws_auth_token else {
+            return true;
+        };
+        expected.len() == presented.len()
+            && expected
+                .bytes()
+                .zip(presented.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+    
+    // Helper method to forget a WebSocket channel and ask the runtime to close it
+    fn close_ws_channel(&mut self, channel_id: u32) {
+        self.active_ws_connections.remove(&channel_id);
+        self.ws_rate.remove(&channel_id);
+        if let Some(server) = hyperware_app_common::get_server() {
+            let _ = server.send_ws_message(channel_id, WsMessageType::Close, Vec::new());
+        }
+    }
+    
+    // Helper method to send an event to a WebSocket channel whether or not it's subscribed,
+    // stamped with the current sequence number. Unsubscribed channels get the current format,
+    // since the client's version is unknown; legacy clients get `ServerWsEvent`s regardless.
+    fn send_ws_notice(&self, channel_id: u32, event: &ServerWsEvent) {
+        let Some(server) = hyperware_app_common::get_server() else {
+            return;
+        };
+        let version = self
+            .active_ws_connections
+            .get(&channel_id)
+            .map_or(WS_PROTOCOL_VERSION, |connection| connection.protocol_version.max(WS_EVENTS_VERSION));
+        if let Some(payload) = event.encode(version, self.ws_seq) {
+            let _ = server.send_ws_message(channel_id, WsMessageType::Binary, payload);
+        }
+    }
+    
+    // Helper method to count an inbound message against its channel's limit. Returns false once
+    // the channel has sent more than WS_RATE_LIMIT messages in the current window; the first
+    // rejected message is answered with `RateLimited`, later ones are dropped silently.
+    fn allow_ws_message(&mut self, channel_id: u32) -> bool {
+        let now = now_secs();
+        let rate = self.ws_rate.entry(channel_id).or_insert_with(|| RateState::new(now));
+        if rate.admit(now) {
+            return true;
+        }
+        if !rate.notified {
+            rate.notified = true;
+            let retry_after_secs = (rate.window_start + WS_RATE_WINDOW_SECS).saturating_sub(now);
+            self.send_ws_notice(channel_id, &ServerWsEvent::RateLimited { retry_after_secs });
+        }
+        false
+    }
+    
+    // Helper method to broadcast an event to the connected WebSocket clients that want it (see
+    // `ServerWsEvent::wanted_by`), encoding it once per protocol version. The event takes the next
+    // sequence number and is buffered for clients that resume later, even with nobody connected.
+    // Channels that fail to send are dropped.
+    fn broadcast_event(&mut self, event: &ServerWsEvent) {
+        self.ws_seq += 1;
+        let seq = self.ws_seq;
+        self.ws_replay.push_back((seq, event.clone()));
+        if self.ws_replay.len() > WS_REPLAY_EVENTS {
+            self.ws_replay.pop_front();
+        }
+        
+        let Some(server) = hyperware_app_common::get_server() else {
+            return;
+        };
+        let mut payloads: HashMap<u32, Option<Vec<u8>>> = HashMap::new();
+        let mut dead_channels = Vec::new();
+        for (channel_id, connection) in &self.active_ws_connections {
+            if !event.wanted_by(connection) {
+                continue;
+            }
+            let version = connection.protocol_version.min(WS_PROTOCOL_VERSION);
+            let payload = payloads.entry(version).or_insert_with(|| event.encode(version, seq));
+            if let Some(payload) = payload {
+                if server.send_ws_message(*channel_id, WsMessageType::Binary, payload.clone()).is_err() {
+                    dead_channels.push(*channel_id);
+                }
+            }
+        }
+        for channel_id in dead_channels {
+            self.active_ws_connections.remove(&channel_id);
+        }
+    }
+    
+    // Helper method to drop connections that haven't sent anything within WS_STALE_AFTER_SECS,
+    // along with limiter state from windows that have ended
+    fn evict_stale_connections(&mut self) {
+        let cutoff = now_secs().saturating_sub(WS_STALE_AFTER_SECS);
+        let before = self.active_ws_connections.len();
+        self.active_ws_connections
+            .retain(|_, connection| connection.last_seen >= cutoff);
+        let evicted = before - self.active_ws_connections.len();
+        if evicted > 0 {
+            hyperware_process_lib::logging::info!("Evicted {} stale WebSocket connections", evicted);
+        }
+        
+        // Channels that closed without a Close frame would otherwise keep their limiter state
+        let window_cutoff = now_secs().saturating_sub(WS_RATE_WINDOW_SECS);
+        self.ws_rate.retain(|_, rate| rate.window_start >= window_cutoff);
+    }
+    
+    // Helper method to send each connected WebSocket client the tasks matching its filter. The
+    // snapshot takes a sequence number of its own and empties the replay buffer, since the changes
+    // it carries aren't in it; clients resuming from before it get a fresh snapshot instead.
+    fn broadcast_snapshot(&mut self) {
+        self.ws_seq += 1;
+        self.ws_replay.clear();
+        let channel_ids: Vec<u32> = self.active_ws_connections.keys().copied().collect();
+        for channel_id in channel_ids {
+            self.send_snapshot(channel_id);
+        }
+    }
+    
+    // Helper method to send one WebSocket client the active tasks matching its filter
+    fn send_snapshot(&mut self, channel_id: u32) {
+        let Some(connection) = self.active_ws_connections.get(&channel_id) else {
+            return;
+        };
+        let tasks = self
+            .active_tasks()
+            .filter(|task| connection.filter.matches(task))
+            .cloned()
+            .collect();
+        self.send_snapshot_tasks(channel_id, tasks);
+    }
+    
+    // Helper method to send a snapshot to one WebSocket client
+    fn send_snapshot_tasks(&mut self, channel_id: u32, tasks: Vec<Task>) {
+        let Some(connection) = self.active_ws_connections.get(&channel_id) else {
+            return;
+        };
+        let seq = self.ws_seq;
+        for event in snapshot_events(connection.protocol_version, connection.gzip, tasks) {
+            self.send_ws_event(channel_id, seq, &event);
+        }
+    }
+    
+    // Helper method to register a subscriber and list the events to greet it with, each with the
+    // sequence number to stamp it with. A channel that isn't subscribed yet is refused, returning
+    // None, once `max_ws_connections` are open; re-subscribing an existing channel always succeeds.
+    fn subscribe(
+        &mut self,
+        channel_id: u32,
+        connection: WsConnection,
+        resume: Option<(&str, u64)>,
+    ) -> Option<Vec<(u64, ServerWsEvent)>> {
+        if !self.active_ws_connections.contains_key(&channel_id)
+            && self.active_ws_connections.len() >= self.max_ws_connections
+        {
+            hyperware_process_lib::logging::warn!(
+                "Refused WebSocket subscription on channel {}: {} connections open, limit {}",
+                channel_id,
+                self.active_ws_connections.len(),
+                self.max_ws_connections
+            );
+            return None;
+        }
+        
+        // Greet the client with the heartbeat and reconnect policy, then send current matching
+        // tasks as initial data, or the events it missed if it's resuming
+        let events = self.subscribe_events(&connection, resume);
+        self.active_ws_connections.insert(channel_id, connection);
+        Some(events)
+    }
+    
+    // Helper method listing what a client subscribing as `connection` is sent, with the sequence
+    // number to stamp each with: a `Hello` with the heartbeat and reconnect policy, run id and
+    // current sequence number, then a snapshot of the active tasks matching its filter, gzipped if
+    // asked. A client resuming from a `(run_id, since_seq)` of this run gets the buffered events it
+    // missed instead, with their original numbers, if none of them have been dropped. Legacy
+    // clients skip the `Hello`, since it has no legacy encoding.
+    fn subscribe_events(&self, connection: &WsConnection, resume: Option<(&str, u64)>) -> Vec<(u64, ServerWsEvent)> {
+        let hello = ServerWsEvent::Hello {
+            heartbeat_secs: WS_HEARTBEAT_SECS,
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            reconnect_initial_secs: WS_RECONNECT_INITIAL_SECS,
+            reconnect_max_secs: WS_RECONNECT_MAX_SECS,
+            run_id: self.ws_run_id.clone(),
+            seq: self.ws_seq,
+        };
+        let mut events = vec![(self.ws_seq, hello)];
+        if let Some(missed) = resume.and_then(|(run_id, since_seq)| self.missed_events(run_id, since_seq)) {
+            events.extend(
+                missed
+                    .filter(|(_, event)| event.wanted_by(connection) && event.replayable())
+                    .cloned(),
+            );
+            return events;
+        }
+        
+        let tasks = self.active_tasks().filter(|task| connection.filter.matches(task)).cloned().collect();
+        events.extend(
+            snapshot_events(connection.protocol_version, connection.gzip, tasks)
+                .into_iter()
+                .map(|event| (self.ws_seq, event)),
+        );
+        events
+    }
+    
+    // Helper method returning the buffered events after `since_seq`, or None if `run_id` isn't this
+    // run, some events have already been dropped from the buffer, or `since_seq` is ahead of this
+    // run's numbering
+    fn missed_events(&self, run_id: &str, since_seq: u64) -> Option<impl Iterator<Item = &(u64, ServerWsEvent)>> {
+        if run_id != self.ws_run_id || since_seq > self.ws_seq {
+            return None;
+        }
+        let all_buffered = since_seq == self.ws_seq
+            || self.ws_replay.front().is_some_and(|(oldest, _)| *oldest <= since_seq + 1);
+        if !all_buffered {
+            return None;
+        }
+        Some(self.ws_replay.iter().filter(move |(seq, _)| *seq > since_seq))
+    }
+    
+    // Helper method to broadcast updates to the connected WebSocket clients whose filter matches.
+    // A client filtering on status stops hearing about a task once it leaves that status.
+    fn broadcast_task_update(&mut self, task: &Task) {
+        self.broadcast_event(&ServerWsEvent::TaskUpserted(task.clone()));
+    }
+    
+    // Helper method to send the current task counts to the WebSocket clients subscribed to stats,
+    // if they changed since the last send. Handlers call it once per request after all their
+    // changes, so a bulk operation produces a single event.
+    fn broadcast_stats(&mut self) {
+        if !self.active_ws_connections.values().any(|connection| connection.stats) {
+            self.last_broadcast_counts = None;
+            return;
+        }
+        let counts = self.task_counts();
+        if self.last_broadcast_counts.as_ref() == Some(&counts) {
+            return;
+        }
+        self.broadcast_event(&ServerWsEvent::StatsUpdated(counts.clone()));
+        self.last_broadcast_counts = Some(counts);
+    }
+    
+    // Helper method to tell all connected WebSocket clients that a task was removed
+    fn broadcast_task_deletion(&mut self, task_id: &str) {
+        self.broadcast_event(&ServerWsEvent::TaskDeleted { id: task_id.to_string() });
+    }
+}
+
+// Supporting types for the application
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NewTaskRequest {
+    title: String,
+    description: String,
+    // Also accepts the single `assigned_to` older clients send
+    #[serde(default, alias = "assigned_to", deserialize_with = "deserialize_assignees")]
+    assignees: Vec<String>,
+    #[serde(default)]
+    priority: TaskPriority,
+    #[serde(default)]
+    due_at: Option<u64>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    parent_id: Option<String>,
+    #[serde(default)]
+    recurrence: Option<RecurrenceRule>,
+    // Client-chosen key making retries of `create_task` return the first attempt's task
+    #[serde(default)]
+    idempotency_key: Option<String>,
+    #[serde(default, serialize_with = "serialize_pairs", deserialize_with = "deserialize_pairs")]
+    metadata: HashMap<String, String>,
+    // Expected effort, in seconds
+    #[serde(default)]
+    estimate_secs: Option<u64>,
+    // Traces the request through logs and storage; generated when absent
+    #[serde(default)]
+    correlation_id: Option<String>,
+}
+
+/// How long `create_task` remembers an idempotency key, in seconds
+const IDEMPOTENCY_KEY_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Maximum number of idempotency keys remembered; the oldest are forgotten first
+const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+/// Maximum task title length, in characters
+const MAX_TITLE_LEN: usize = 256;
+
+/// Maximum task description length, in characters
+const MAX_DESCRIPTION_LEN: usize = 8192;
+
+/// Maximum number of metadata keys per task
+const MAX_METADATA_KEYS: usize = 32;
+
+/// Maximum metadata key length, in characters
+const MAX_METADATA_KEY_LEN: usize = 64;
+
+/// Maximum metadata value length, in characters
+const MAX_METADATA_VALUE_LEN: usize = 1024;
+
+impl NewTaskRequest {
+    /// Bytes of free-form text the request carries, for batch size limits
+    fn text_bytes(&self) -> usize {
+        self.title.len()
+            + self.description.len()
+            + self.tags.iter().map(String::len).sum::<usize>()
+            + self.metadata.iter().map(|(key, value)| key.len() + value.len()).sum::<usize>()
+    }
+    
+    /// Check the request describes a creatable task. Limits apply to the trimmed
+    /// title and description, which is what `Task::from_request` stores.
+    fn validate(&self) -> Result<(), String> {
+        validate_text(&self.title, &self.description)?;
+        validate_metadata(&self.metadata)?;
+        if self.recurrence.as_ref().is_some_and(|rule| rule.interval_secs == 0) {
+            return Err("Recurrence interval must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A reusable blueprint for tasks, saved with `save_template`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TaskTemplate {
+    title: String,
+    description: String,
+    #[serde(default)]
+    priority: TaskPriority,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl TaskTemplate {
+    /// The creation request for a task from this template, with the fields set in `overrides`
+    /// and `priority` taking precedence. A blank title, description or tag list counts as unset;
+    /// the priority in `overrides` is replaced by `priority`, or the template's if that is None.
+    fn apply(&self, overrides: NewTaskRequest, priority: Option<TaskPriority>) -> NewTaskRequest {
+        let title = if overrides.title.trim().is_empty() { self.title.clone() } else { overrides.title };
+        let description = if overrides.description.trim().is_empty() {
+            self.description.clone()
+        } else {
+            overrides.description
+        };
+        let priority = priority.unwrap_or_else(|| self.priority.clone());
+        let tags = if overrides.tags.is_empty() { self.tags.clone() } else { overrides.tags };
+        NewTaskRequest { title, description, priority, tags, ..overrides }
+    }
+}
+
+/// Maximum number of tasks in one bulk create or import
+const MAX_BATCH_LEN: usize = 1000;
+
+/// Maximum free-form text across one bulk create or import, in bytes
+const MAX_BATCH_TEXT_BYTES: usize = 1024 * 1024;
+
+// Check a whole batch against the size limits, before any of it is validated or stored
+fn check_batch_limits(len: usize, text_bytes: usize) -> Result<(), String> {
+    if len > MAX_BATCH_LEN {
+        return Err(format!("Batch of {} tasks exceeds the limit of {}", len, MAX_BATCH_LEN));
+    }
+    if text_bytes > MAX_BATCH_TEXT_BYTES {
+        return Err(format!(
+            "Batch carries {} bytes of text, over the limit of {}",
+            text_bytes, MAX_BATCH_TEXT_BYTES
+        ));
+    }
+    Ok(())
+}
+
+// Check a task's title and description against the length limits, ignoring surrounding whitespace
+fn validate_text(title: &str, description: &str) -> Result<(), String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Title must not be empty".to_string());
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        return Err(format!("Title exceeds {} characters", MAX_TITLE_LEN));
+    }
+    if description.trim().chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(format!("Description exceeds {} characters", MAX_DESCRIPTION_LEN));
+    }
+    Ok(())
+}
+
+// Check a task's whole metadata map against the key count and length limits
+fn validate_metadata(metadata: &HashMap<String, String>) -> Result<(), String> {
+    if metadata.len() > MAX_METADATA_KEYS {
+        return Err(format!("Metadata exceeds {} keys", MAX_METADATA_KEYS));
+    }
+    for (key, value) in metadata {
+        validate_metadata_entry(key, value)?;
+    }
+    Ok(())
+}
+
+// Check a single metadata key and value against the length limits
+fn validate_metadata_entry(key: &str, value: &str) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("Metadata key must not be empty".to_string());
+    }
+    if key.chars().count() > MAX_METADATA_KEY_LEN {
+        return Err(format!("Metadata key exceeds {} characters", MAX_METADATA_KEY_LEN));
+    }
+    if value.chars().count() > MAX_METADATA_VALUE_LEN {
+        return Err(format!("Metadata value exceeds {} characters", MAX_METADATA_VALUE_LEN));
+    }
+    Ok(())
+}
+
+/// The fields a `patch_task` patch sets; None leaves a field alone
+#[derive(Debug, Default)]
+struct TaskPatch {
+    title: Option<String>,
+    description: Option<String>,
+    status: Option<TaskStatus>,
+    assignees: Option<Vec<String>>,
+    priority: Option<TaskPriority>,
+    tags: Option<Vec<String>>,
+    estimate_secs: Option<Option<u64>>,
+}
+
+impl TaskPatch {
+    /// Read a JSON merge patch, returning it with the unknown keys it contained. Unknown keys
+    /// are an error when `strict`. A null `description`, `assigned_to`, `priority`, `tags` or
+    /// `estimate_secs` resets that field; `title` and `status` can't be null.
+    fn parse(patch: serde_json::Value, strict: bool) -> Result<(TaskPatch, Vec<String>), String> {
+        let serde_json::Value::Object(fields) = patch else {
+            return Err("Patch must be a JSON object".to_string());
+        };
+        
+        let mut parsed = TaskPatch::default();
+        let mut unknown = Vec::new();
+        for (key, value) in fields {
+            let invalid = |e: serde_json::Error| format!("Invalid {}: {}", key, e);
+            match key.as_str() {
+                "title" => parsed.title = Some(serde_json::from_value(value).map_err(invalid)?),
+                "description" => {
+                    let description: Option<String> = serde_json::from_value(value).map_err(invalid)?;
+                    parsed.description = Some(description.unwrap_or_default());
+                }
+                "status" => parsed.status = Some(serde_json::from_value(value).map_err(invalid)?),
+                "assigned_to" => {
+                    // A single name, as in the old `assigned_to` field, or a list of them
+                    let assignees = match value {
+                        serde_json::Value::Null => Vec::new(),
+                        serde_json::Value::String(assignee) => vec![assignee],
+                        value => serde_json::from_value(value).map_err(invalid)?,
+                    };
+                    parsed.assignees = Some(assignees);
+                }
+                "priority" => {
+                    let priority: Option<TaskPriority> = serde_json::from_value(value).map_err(invalid)?;
+                    parsed.priority = Some(priority.unwrap_or_default());
+                }
+                "tags" => {
+                    let tags: Option<Vec<String>> = serde_json::from_value(value).map_err(invalid)?;
+                    parsed.tags = Some(tags.unwrap_or_default());
+                }
+                "estimate_secs" => parsed.estimate_secs = Some(serde_json::from_value(value).map_err(invalid)?),
+                _ => unknown.push(key.clone()),
+            }
+        }
+        
+        if strict && !unknown.is_empty() {
+            return Err(format!("Unknown patch keys: {}", unknown.join(", ")));
+        }
+        Ok((parsed, unknown))
+    }
+    
+    /// Whether the patch leaves every field alone
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.status.is_none()
+            && self.assignees.is_none()
+            && self.priority.is_none()
+            && self.tags.is_none()
+            && self.estimate_secs.is_none()
+    }
+}
+
+/// How assignees are checked. `Address` requires each to parse as a node/process address
+/// (`node@process:package:publisher`), which assignment notifications are sent to.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+enum AssigneeFormat {
+    #[default]
+    Freeform,
+    Address,
+}
+
+impl AssigneeFormat {
+    /// Reject the first assignee that doesn't match the format. Blank entries are dropped
+    /// when assignees are normalized, so they're skipped here.
+    fn check(&self, assignees: &[String]) -> Result<(), String> {
+        if *self == Self::Freeform {
+            return Ok(());
+        }
+        for assignee in assignees.iter().map(|assignee| assignee.trim()).filter(|assignee| !assignee.is_empty()) {
+            if assignee.parse::<Address>().is_err() {
+                return Err(format!(
+                    "Assignee {:?} is not a valid address; expected node@process:package:publisher",
+                    assignee
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What `import_tasks` does with a task whose id is already in use
+#[derive(Debug, Serialize, Deserialize)]
+enum ImportConflict {
+    Skip,
+    RegenerateId,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskStatusUpdateRequest {
+    task_id: String,
+    new_status: TaskStatus,
+    // Version the client last saw; the update is rejected if the task has changed since
+    #[serde(default)]
+    expected_version: Option<u64>,
+    // Traces the request through logs and storage; generated when absent
+    #[serde(default)]
+    correlation_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskUpdateRequest {
+    task_id: String,
+    title: Option<String>,
+    description: Option<String>,
+    // Replaces the whole assignee list; also accepts the single `assigned_to` older clients send
+    #[serde(default, alias = "assigned_to", deserialize_with = "deserialize_optional_assignees")]
+    assignees: Option<Vec<String>>,
+    new_status: Option<TaskStatus>,
+    // Move the task under a new parent
+    #[serde(default)]
+    parent_id: Option<String>,
+    // Version the client last saw; the update is rejected if the task has changed since
+    #[serde(default)]
+    expected_version: Option<u64>,
+    // Traces the request through logs and storage; generated when absent
+    #[serde(default)]
+    correlation_id: Option<String>,
+}
+
+/// Content type every HTTP response should carry
+const JSON_CONTENT_TYPE: &str = "application/json; charset=utf-8";
+
+/// An `#[http]` handler's return value together with the headers its reply needs.
+///
+/// No response carries these headers yet. After an `#[http]` handler returns, the code
+/// hyperprocess-macro generates serializes the value and calls
+/// `send_response(StatusCode::OK, None, body)`; the handler never sees that call, so it can't
+/// pass headers, and answering the request itself would leave the macro's reply as a second
+/// response. The macro change needed: when a handler's declared return type is `JsonReply<_>`,
+/// pass `Some(reply.headers())` instead of `None`. `JsonReply` serializes exactly like the value
+/// it wraps, so the body is unchanged either way. `create_task` and `get_all_tasks` already
+/// return one; the other handlers can switch over once the macro honors it.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+struct JsonReply<T>(T);
+
+impl<T> JsonReply<T> {
+    /// Headers to send with the serialized value
+    #[allow(dead_code)] // read by the macro's HTTP return path once it passes headers on
+    fn headers(&self) -> HashMap<String, String> {
+        HashMap::from([("Content-Type".to_string(), JSON_CONTENT_TYPE.to_string())])
+    }
+}
+
+/// A handler's reply, or a `BadRequestBody` when its input couldn't be parsed.
+///
+/// hyperprocess-macro parses an `#[http]` body into its request enum before any handler runs and
+/// answers a failure itself with a plain-text error; it has no hook for a custom reply. Handlers
+/// that want a structured error take the JSON as a string, parse it themselves and return this.
+/// Untagged, so a parsed reply is sent exactly as the value it wraps.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ParsedReply<T> {
+    Parsed(T),
+    Malformed(BadRequestBody),
+}
+
+/// Body sent for input that couldn't be parsed: `{"success": false, "error": "bad_request", ...}`
+#[derive(Debug, Serialize, Deserialize)]
+struct BadRequestBody {
+    success: bool,
+    error: String,
+    message: String,
+}
+
+impl BadRequestBody {
+    fn new(message: impl Into<String>) -> Self {
+        BadRequestBody { success: false, error: "bad_request".to_string(), message: message.into() }
+    }
+}
+
+/// Result of a task operation.
+///
+/// `status_code` is the HTTP status the response stands for: 200, 400 for invalid input,
+/// 404 for unknown ids, 409 when the request conflicts with the task's current state.
+/// `error` says the same in a form callers can match on; `message` is for people.
+/// The `#[http]` return path in hyperprocess-macro always replies `200 OK` with the
+/// serialized return value once the handler returns, so a handler can't pick another status
+/// and for now the status only travels in the body. The minimal upstream change is to let `#[http]`
+/// handlers return `(StatusCode, T)` and pass the status through to `send_response`;
+/// `get_task`, `update_task_status` and `delete_task` can then return `status_code` next to
+/// the response without changing the JSON clients see.
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskResponse {
+    success: bool,
+    task: Option<Task>,
+    storage_status: bool,
+    message: String,
+    status_code: u16,
+    // The request's correlation id, for handlers whose request carries one
+    #[serde(default)]
+    correlation_id: Option<String>,
+    // Why the operation failed. Also set to `StorageUnavailable` on a success whose change is
+    // only held locally until storage is reachable again.
+    #[serde(default)]
+    error: Option<TaskError>,
+}
+
+/// Machine-readable reason carried by a `TaskResponse`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum TaskError {
+    NotFound,
+    // The request itself is invalid; the reason is also in `message`
+    Validation(String),
+    StorageUnavailable,
+    // The task changed since the version the client expected
+    VersionConflict,
+    IllegalTransition { from: TaskStatus, to: TaskStatus },
+    // The task can't start while these blockers aren't Completed
+    Blocked(Vec<String>),
+    // Any other clash with the task's current state, such as having subtasks
+    Conflict(String),
+    // Only the task's owner or an admin may make this change
+    Forbidden,
+}
+
+impl TaskResponse {
+    /// Successful operation; `storage_status` reports whether the change reached storage
+    fn ok(task: Task, storage_status: bool, message: impl Into<String>) -> Self {
+        TaskResponse {
+            success: true,
+            task: Some(task),
+            storage_status,
+            message: message.into(),
+            status_code: 200,
+            correlation_id: None,
+            error: (!storage_status).then_some(TaskError::StorageUnavailable),
+        }
+    }
+    
+    /// No task with the given id
+    fn not_found(task_id: &str) -> Self {
+        TaskResponse {
+            success: false,
+            task: None,
+            storage_status: false,
+            message: format!("Task {} not found", task_id),
+            status_code: 404,
+            correlation_id: None,
+            error: Some(TaskError::NotFound),
+        }
+    }
+    
+    /// The request itself is invalid
+    fn bad_request(message: impl Into<String>) -> Self {
+        let message = message.into();
+        TaskResponse {
+            success: false,
+            task: None,
+            storage_status: false,
+            error: Some(TaskError::Validation(message.clone())),
+            message,
+            status_code: 400,
+            correlation_id: None,
+        }
+    }
+    
+    /// The request is valid but conflicts with the task's current state, returned unchanged
+    fn conflict(task: Option<Task>, message: impl Into<String>) -> Self {
+        let message = message.into();
+        TaskResponse {
+            success: false,
+            task,
+            storage_status: false,
+            error: Some(TaskError::Conflict(message.clone())),
+            message,
+            status_code: 409,
+            correlation_id: None,
+        }
+    }
+    
+    /// The client's copy of the task is stale; the current task is returned
+    fn version_conflict(task: Task, message: impl Into<String>) -> Self {
+        TaskResponse {
+            error: Some(TaskError::VersionConflict),
+            ..TaskResponse::conflict(Some(task), message)
+        }
+    }
+    
+    /// The task can't move to status `to` from its current status; the task is returned unchanged
+    fn illegal_transition(task: Task, to: TaskStatus, message: impl Into<String>) -> Self {
+        let from = task.status.clone();
+        TaskResponse {
+            error: Some(TaskError::IllegalTransition { from, to }),
+            ..TaskResponse::conflict(Some(task), message)
+        }
+    }
+    
+    /// The task can't start until the listed blockers are Completed; the task is returned unchanged
+    fn blocked(task: Task, blockers: Vec<String>) -> Self {
+        let message = format!("Task is blocked by unfinished tasks: {}", blockers.join(", "));
+        TaskResponse {
+            error: Some(TaskError::Blocked(blockers)),
+            ..TaskResponse::conflict(Some(task), message)
+        }
+    }
+    
+    /// The caller doesn't own the task and isn't an admin; the task is returned unchanged
+    fn forbidden(task: Task, message: impl Into<String>) -> Self {
+        TaskResponse {
+            status_code: 403,
+            error: Some(TaskError::Forbidden),
+            ..TaskResponse::conflict(Some(task), message)
+        }
+    }
+    
+    /// Echo the request's correlation id back to the caller
+    fn with_correlation_id(mut self, correlation_id: String) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BulkCreateResponse {
+    created: Vec<Task>,
+    // Human-readable reasons for tasks that were not (fully) created
+    failed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TasksByIdsResponse {
+    // Existing tasks, in request order
+    found: Vec<Task>,
+    // Requested ids with no matching task
+    missing: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BulkDeleteResponse {
+    // Ids of the removed tasks
+    deleted: Vec<String>,
+    count: u64,
+    // Human-readable reasons for tasks that were kept or not removed from storage
+    failed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BulkUpdateResponse {
+    // Tasks whose status changed, once each at their final state, in request order
+    updated: Vec<Task>,
+    // Updates that were not applied
+    failed: Vec<BulkUpdateFailure>,
+    // Human-readable reasons for updated tasks that are only held locally until storage is reachable
+    unsynced: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BulkUpdateFailure {
+    task_id: String,
+    error: TaskError,
+    message: String,
+}
+
+impl BulkUpdateFailure {
+    fn from_response(task_id: String, response: TaskResponse) -> Self {
+        BulkUpdateFailure {
+            task_id,
+            error: response.error.unwrap_or_else(|| TaskError::Conflict(response.message.clone())),
+            message: response.message,
+        }
+    }
+}
+
+/// The part of a task a search matched, in ranking order
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum MatchField {
+    Title,
+    Description,
+    Comment,
+}
+
+/// A task found by `search_tasks`
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchResult {
+    task: Task,
+    matched_in: MatchField,
+}
+
+/// Criteria for `query_tasks`; unset fields match any task
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TaskFilter {
+    #[serde(default)]
+    status: Option<TaskStatus>,
+    #[serde(default)]
+    priority: Option<TaskPriority>,
+    #[serde(default)]
+    assignee: Option<String>,
+    #[serde(default)]
+    tag: Option<String>,
+    // Substring of the title or description
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// Task field `get_all_tasks_sorted` orders by
+#[derive(Debug, Serialize, Deserialize)]
+enum SortField {
+    CreatedAt,
+    Title,
+    Status,
+    Priority,
+    DueAt,
+    LastActivity,
+    Manual,
+}
+
+impl SortField {
+    /// Ascending order of two tasks by this field. Titles compare case-insensitively, and tasks
+    /// without a due date sort after those with one.
+    fn compare(&self, a: &Task, b: &Task) -> std::cmp::Ordering {
+        match self {
+            SortField::CreatedAt => a.created_at.cmp(&b.created_at),
+            SortField::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            SortField::Status => a.status.cmp(&b.status),
+            SortField::Priority => a.priority.cmp(&b.priority),
+            SortField::DueAt => match (a.due_at, b.due_at) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
+            SortField::LastActivity => a.last_activity_at.cmp(&b.last_activity_at),
+            SortField::Manual => a.sort_order.total_cmp(&b.sort_order),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SortSpec {
+    field: SortField,
+    descending: bool,
+}
+
+/// Reply to `get_all_tasks_if_changed`, carrying the current epoch for the next poll
+#[derive(Debug, Serialize, Deserialize)]
+enum TasksSince {
+    Unchanged { epoch: u64 },
+    Changed { epoch: u64, tasks: Vec<Task> },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PageRequest {
+    offset: usize,
+    limit: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskPage {
+    tasks: Vec<Task>,
+    total: u64,
+    offset: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CursorPage {
+    tasks: Vec<Task>,
+    // Opaque position to pass to the next `get_tasks_after` call; None after the last page
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CompletionMetrics {
+    created_in_window: u64,
+    completed_in_window: u64,
+    completion_rate: f64,
+    // Mean seconds from creation to completion over the window's completions; None without any
+    avg_time_to_complete_secs: Option<u64>,
+}
+
+/// Effort totals from `get_effort_rollup`, in seconds; sums saturate rather than overflow
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EffortRollup {
+    task_count: u64,
+    // Tasks without an estimate, which add logged time but no estimate
+    unestimated_tasks: u64,
+    estimated_secs: u64,
+    logged_secs: u64,
+    remaining_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskManagerStats {
+    total_tasks: u64,
+    pending_tasks: u64,
+    completed_tasks: u64,
+    creation_count: u64,
+    request_count: u64,
+    #[serde(serialize_with = "serialize_pairs", deserialize_with = "deserialize_pairs")]
+    endpoint_hits: HashMap<String, u64>,
+    ws_connection_count: u64,
+}
+
+/// How long deletions are remembered for sync clients, in seconds
+const TOMBSTONE_RETENTION_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Maximum number of deletions remembered; the oldest are forgotten first
+const MAX_TOMBSTONES: usize = 10_000;
+
+/// A deleted task, as reported by `get_tombstones_since`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Tombstone {
+    id: String,
+    deleted_at: u64,
+}
+
+/// One history event in the feed from `get_recent_activity`
+#[derive(Debug, Serialize, Deserialize)]
+struct ActivityEntry {
+    task_id: String,
+    // The task's current title
+    title: String,
+    kind: TaskEventKind,
+    at: u64,
+}
+
+/// Just enough of a task to list it; see `list_task_summaries`
 #[derive(Debug, Serialize, Deserialize)]
-struct NewTaskRequest {
+struct TaskSummary {
+    id: String,
     title: String,
-    description: String,
-    assigned_to: Option<String>,
+    status: TaskStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct TaskStatusUpdateRequest {
-    task_id: String,
-    new_status: TaskStatus,
+struct TagCount {
+    tag: String,
+    count: u64,
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AssigneeStats {
+    pending: u64,
+    in_progress: u64,
+    completed: u64,
+}
+
+/// Outcome of `sync_all_to_storage`
 #[derive(Debug, Serialize, Deserialize)]
-struct TaskResponse {
-    success: bool,
-    task: Option<Task>,
-    storage_status: bool,
-    message: String,
+struct SyncReport {
+    attempted: u64,
+    succeeded: u64,
+    // Human-readable reasons for tasks that couldn't be stored
+    failed: Vec<String>,
 }
 
+/// Size of one storage call carrying every task in each encoding, from `compare_storage_encodings`
 #[derive(Debug, Serialize, Deserialize)]
-struct TaskManagerStats {
-    total_tasks: u64,
-    pending_tasks: u64,
-    completed_tasks: u64,
-    creation_count: u64,
-    request_count: u64,
+struct StorageEncodingReport {
+    task_count: u64,
+    // Request body sizes in bytes, including the JSON envelope
+    json_bytes: u64,
+    packed_bytes: u64,
+    // Whether both encodings decode back to the tasks they were made from
+    round_trip_equal: bool,
+}
+
+/// Differences between local tasks and the storage process, by task id
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ReconcileReport {
+    only_local: Vec<String>,
+    only_storage: Vec<String>,
+    // Present on both sides with different contents or versions
+    conflicting: Vec<String>,
+    // Set when storage couldn't be read, in which case the lists are empty
+    storage_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HealthStatus {
+    ok: bool,
+    storage_reachable: bool,
+    task_count: u64,
+    uptime_secs: u64,
+    ws_connection_count: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct TaskCounts {
+    total: u64,
+    pending: u64,
+    in_progress: u64,
+    completed: u64,
+    cancelled: u64,
+}
+
+/// Active tasks grouped by status, from `get_board`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TaskBoard {
+    pending: Vec<Task>,
+    in_progress: Vec<Task>,
+    completed: Vec<Task>,
+    cancelled: Vec<Task>,
+}
+
+/// Interval between periodic maintenance ticks, in seconds
+const TICK_INTERVAL_SECS: u64 = 60;
+
+/// Current WebSocket protocol version. Clients subscribing without a version
+/// (version 0) get the legacy bare `Task` / `Vec<Task>` payloads.
+const WS_PROTOCOL_VERSION: u32 = 2;
+
+/// First protocol version sent `ServerWsEvent`s rather than legacy payloads. Version 1 clients
+/// get them bare; later ones get each wrapped in a `SequencedWsEvent`.
+const WS_EVENTS_VERSION: u32 = 1;
+
+/// Broadcast events kept for replay to resuming clients; clients further behind get a snapshot
+const WS_REPLAY_EVENTS: usize = 256;
+
+/// Maximum tasks per `SnapshotChunk`, keeping frames well under typical size limits
+const WS_SNAPSHOT_CHUNK_TASKS: usize = 100;
+
+/// Expected interval between client heartbeats, in seconds
+const WS_HEARTBEAT_SECS: u64 = 30;
+
+/// Connections silent for this long are considered dead and evicted, in seconds
+const WS_STALE_AFTER_SECS: u64 = 3 * WS_HEARTBEAT_SECS;
+
+/// Delay clients are told to wait before their first reconnect attempt, in seconds. Clients
+/// double it after each failed attempt, up to WS_RECONNECT_MAX_SECS.
+const WS_RECONNECT_INITIAL_SECS: u64 = 1;
+
+/// Longest delay clients are told to wait between reconnect attempts, in seconds
+const WS_RECONNECT_MAX_SECS: u64 = 60;
+
+/// Length of the window inbound WebSocket messages are counted over, in seconds
+const WS_RATE_WINDOW_SECS: u64 = 10;
+
+/// Maximum inbound WebSocket messages per channel per window
+const WS_RATE_LIMIT: u32 = 50;
+
+/// The one snapshot compression clients can ask for when subscribing
+const WS_GZIP_ENCODING: &str = "gzip";
+
+/// Default cap on concurrently subscribed WebSocket clients; see `set_max_ws_connections`.
+/// A hard ceiling for clients that churn faster than the stale-connection sweep removes them.
+const MAX_WS_CONNECTIONS: usize = 1_000;
+
+#[derive(Debug)]
+struct RateState {
+    // Unix seconds the current window began
+    window_start: u64,
+    // Messages received in the current window
+    count: u32,
+    // Whether the channel was told it's over the limit in this window
+    notified: bool,
+}
+
+impl RateState {
+    /// A window starting at `now` with nothing counted yet
+    fn new(now: u64) -> Self {
+        RateState { window_start: now, count: 0, notified: false }
+    }
+    
+    /// Count a message received at `now`, starting a new window once the current one is over.
+    /// Returns whether the message is within WS_RATE_LIMIT for its window.
+    fn admit(&mut self, now: u64) -> bool {
+        if now >= self.window_start + WS_RATE_WINDOW_SECS {
+            *self = RateState::new(now);
+        }
+        self.count += 1;
+        self.count <= WS_RATE_LIMIT
+    }
+}
+
+#[derive(Debug)]
+struct WsConnection {
+    client_id: String,
+    protocol_version: u32,
+    // Unix seconds of the last frame received on this channel
+    last_seen: u64,
+    // Which task updates the client wants
+    filter: SubscriptionFilter,
+    // Whether the client wants `StatsUpdated` events
+    stats: bool,
+    // Whether snapshots are sent gzipped
+    gzip: bool,
+}
+
+impl WsConnection {
+    /// A current-version connection with no filter, stats or compression
+    fn unfiltered(client_id: String) -> Self {
+        WsConnection {
+            client_id,
+            protocol_version: WS_PROTOCOL_VERSION,
+            last_seen: now_secs(),
+            filter: SubscriptionFilter::default(),
+            stats: false,
+            gzip: false,
+        }
+    }
+}
+
+/// Narrows the task updates a WebSocket client receives; unset fields match any task
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SubscriptionFilter {
+    status: Option<TaskStatus>,
+    assignee: Option<String>,
+}
+
+impl SubscriptionFilter {
+    fn matches(&self, task: &Task) -> bool {
+        self.status.as_ref().is_none_or(|status| *status == task.status)
+            && self
+                .assignee
+                .as_ref()
+                .is_none_or(|assignee| task.assignees.contains(assignee))
+    }
+}
+
+/// Events pushed to WebSocket clients speaking protocol version 1 or later
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum ServerWsEvent {
+    // First event after subscribing. Clients should send a heartbeat every `heartbeat_secs`;
+    // the server evicts connections silent for WS_STALE_AFTER_SECS, a few heartbeats' worth.
+    // After a disconnect, clients wait `reconnect_initial_secs` before reconnecting, doubling
+    // the wait after each failure up to `reconnect_max_secs`. `seq` is the sequence number of
+    // the last broadcast and `run_id` identifies this server run, for clients to pass as
+    // `since_seq` and `run_id` when they resume.
+    Hello {
+        heartbeat_secs: u64,
+        server_version: String,
+        reconnect_initial_secs: u64,
+        reconnect_max_secs: u64,
+        run_id: String,
+        seq: u64,
+    },
+    // Only sent to legacy clients; current clients get chunks
+    Snapshot(Vec<Task>),
+    // One page of a snapshot; `page` counts from 0
+    SnapshotChunk { page: u64, total_pages: u64, tasks: Vec<Task> },
+    // All chunks of a snapshot have been sent; `total` is the number of tasks across them
+    SnapshotComplete { total: u64 },
+    TaskUpserted(Task),
+    TaskDeleted { id: String },
+    TaskOverdue { id: String },
+    TaskArchived { id: String },
+    // A task on another node was assigned to this node
+    TaskAssigned(Task),
+    CommentAdded { task_id: String, comment: TaskComment },
+    // A client message couldn't be handled
+    Error { message: String },
+    // The server is about to restart; clients should reconnect
+    ServerShuttingDown,
+    // Task counts changed; only sent to clients that subscribed with `stats`
+    StatsUpdated(TaskCounts),
+    // The client sent too many messages; further ones are dropped until the window resets
+    RateLimited { retry_after_secs: u64 },
+    // The subscription's token was missing or wrong; the channel is closed right after
+    Unauthorized,
+    // Too many clients are subscribed; the channel is closed right after
+    ServerBusy,
+    // A snapshot event for a client that asked for compression: the event's JSON, compressed
+    // with `encoding` and base64-encoded
+    CompressedSnapshot { encoding: String, data: String },
+}
+
+/// How events reach clients speaking WS_PROTOCOL_VERSION. Broadcasts each take the next `seq`,
+/// so a client that sees a jump has missed events, unless its filter excluded them. Events sent
+/// to one client (greetings, snapshots, errors) repeat the number of the last broadcast, so a
+/// snapshot stamped `seq` reflects every change up to it.
+#[derive(Debug, Serialize)]
+struct SequencedWsEvent<'a> {
+    seq: u64,
+    event: &'a ServerWsEvent,
+}
+
+impl ServerWsEvent {
+    /// Serialize the event, stamped with `seq`, for a client speaking `protocol_version`.
+    /// Returns `None` for events that have no legacy representation.
+    fn encode(&self, protocol_version: u32, seq: u64) -> Option<Vec<u8>> {
+        if protocol_version >= WS_PROTOCOL_VERSION {
+            return serde_json::to_vec(&SequencedWsEvent { seq, event: self }).ok();
+        }
+        if protocol_version >= WS_EVENTS_VERSION {
+            return serde_json::to_vec(self).ok();
+        }
+        match self {
+            ServerWsEvent::Snapshot(tasks) => serde_json::to_vec(tasks).ok(),
+            ServerWsEvent::TaskUpserted(task) => serde_json::to_vec(task).ok(),
+            ServerWsEvent::TaskDeleted { id } => {
+                serde_json::to_vec(&TaskDeletedMessage { deleted_task_id: id.clone() }).ok()
+            }
+            // Events introduced after the legacy format aren't sent to legacy clients
+            _ => None,
+        }
+    }
+    
+    /// Whether a client subscribed as `connection` is sent the event when it's broadcast: task
+    /// updates only if they match its filter, which drops tasks once they leave it, and
+    /// `StatsUpdated` only if it asked for stats
+    fn wanted_by(&self, connection: &WsConnection) -> bool {
+        match self {
+            ServerWsEvent::TaskUpserted(task) => connection.filter.matches(task),
+            ServerWsEvent::StatsUpdated(_) => connection.stats,
+            _ => true,
+        }
+    }
+    
+    /// Whether the event is worth replaying to a resuming client. A shutdown notice from before
+    /// it reconnected is stale.
+    fn replayable(&self) -> bool {
+        !matches!(self, ServerWsEvent::ServerShuttingDown)
+    }
+    
+    /// The event's variant name, as it appears in the serialized event
+    fn name(&self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => name,
+            Ok(serde_json::Value::Object(fields)) => fields.keys().next().cloned().unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+}
+
+// Append one metric family in the Prometheus text format: HELP and TYPE lines, then a sample
+// per `(labels, value)`. `labels` is the text between the braces, already escaped, or empty.
+fn push_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, u64)]) {
+    out.push_str(&format!("# HELP {} {}\n", name, help));
+    out.push_str(&format!("# TYPE {} {}\n", name, kind));
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            out.push_str(&format!("{} {}\n", name, value));
+        } else {
+            out.push_str(&format!("{}{{{}}} {}\n", name, labels, value));
+        }
+    }
+}
+
+// Escape a Prometheus label value: backslash, double quote and newline
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// Lowercase name of a status for metric labels
+fn status_label(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::InProgress => "in_progress",
+        TaskStatus::Completed => "completed",
+        TaskStatus::Cancelled => "cancelled",
+    }
+}
+
+// Opaque paging cursor for the position just after `task`: "<created_at>:<id>", hex-encoded
+// so clients treat it as a token rather than building their own
+fn encode_cursor(task: &Task) -> String {
+    format!("{}:{}", task.created_at, task.id)
+        .bytes()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Inverse of `encode_cursor`; None if `cursor` wasn't produced by it
+fn decode_cursor(cursor: &str) -> Option<(u64, String)> {
+    if cursor.len() % 2 != 0 {
+        return None;
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let decoded = String::from_utf8(bytes).ok()?;
+    let (created_at, id) = decoded.split_once(':')?;
+    Some((created_at.parse().ok()?, id.to_string()))
+}
+
+// The events carrying a snapshot of `tasks` to a client speaking `protocol_version`. Current
+// clients get `SnapshotChunk`s of at most WS_SNAPSHOT_CHUNK_TASKS tasks, oldest first, then a
+// `SnapshotComplete`; legacy clients only understand a single `Vec<Task>`. With `gzip`, current
+// clients get each chunk wrapped in a `CompressedSnapshot`.
+fn snapshot_events(protocol_version: u32, gzip: bool, mut tasks: Vec<Task>) -> Vec<ServerWsEvent> {
+    if protocol_version < WS_EVENTS_VERSION {
+        return vec![ServerWsEvent::Snapshot(tasks)];
+    }
+    
+    tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+    let total = tasks.len() as u64;
+    let total_pages = tasks.len().div_ceil(WS_SNAPSHOT_CHUNK_TASKS) as u64;
+    let mut events: Vec<ServerWsEvent> = tasks
+        .chunks(WS_SNAPSHOT_CHUNK_TASKS)
+        .enumerate()
+        .map(|(page, chunk)| ServerWsEvent::SnapshotChunk {
+            page: page as u64,
+            total_pages,
+            tasks: chunk.to_vec(),
+        })
+        .map(|chunk| if gzip { gzip_event(chunk) } else { chunk })
+        .collect();
+    events.push(ServerWsEvent::SnapshotComplete { total });
+    events
+}
+
+// Wrap an event in a `CompressedSnapshot` holding its gzipped JSON, base64-encoded. The event
+// is returned as is if it can't be compressed.
+fn gzip_event(event: ServerWsEvent) -> ServerWsEvent {
+    let compressed = serde_json::to_vec(&event).ok().and_then(|json| {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json).ok()?;
+        encoder.finish().ok()
+    });
+    match compressed {
+        Some(data) => ServerWsEvent::CompressedSnapshot {
+            encoding: WS_GZIP_ENCODING.to_string(),
+            data: BASE64_STANDARD.encode(data),
+        },
+        None => event,
+    }
+}
+
+// Legacy deletion notice; shaped differently from `Task` so clients can tell them apart
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskDeletedMessage {
+    deleted_task_id: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 enum WebSocketMessage {
-    Subscribe { client_id: String },
+    Subscribe {
+        client_id: String,
+        // Protocol version spoken by the client; omitted by legacy clients
+        #[serde(default)]
+        version: u32,
+        // Only receive updates for matching tasks; omitted to receive everything
+        #[serde(default)]
+        filter: SubscriptionFilter,
+        // Also receive `StatsUpdated` events
+        #[serde(default)]
+        stats: bool,
+        // Must match the token set with `set_ws_token`, if one is set
+        #[serde(default)]
+        auth_token: String,
+        // Snapshot compression the client can decode; only "gzip" is supported, anything else
+        // gets uncompressed snapshots
+        #[serde(default)]
+        compression: Option<String>,
+        // Sequence number of the last event seen before reconnecting. The events since are
+        // replayed instead of a snapshot if they're all still buffered and `run_id` matches.
+        #[serde(default)]
+        since_seq: Option<u64>,
+        // Run id from the `Hello` that `since_seq` was counted in. Numbers restart with the
+        // server, so a resume from another run, or without a run id, gets a snapshot.
+        #[serde(default)]
+        run_id: Option<String>,
+    },
     Unsubscribe,
+    // Ask for a snapshot of the active tasks, optionally only those in one status
+    Query {
+        #[serde(default)]
+        status: Option<TaskStatus>,
+    },
+}
+
+// Whether a subscriber's requested compression is gzip, the only one supported
+fn accepts_gzip(compression: Option<&str>) -> bool {
+    compression.is_some_and(|compression| compression.trim().eq_ignore_ascii_case(WS_GZIP_ENCODING))
+}
+
+// Tags are compared trimmed and lowercased
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+// Normalize a list of tags, dropping empty and duplicate entries while keeping their order
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = normalize_tag(&tag);
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+// A request's correlation id, or a fresh one when the client didn't send one
+fn correlation_id_or_new(correlation_id: Option<String>) -> String {
+    correlation_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+// Message returned when a status change is rejected by `TaskStatus::can_transition_to`
+fn illegal_transition_message(from: &TaskStatus, to: &TaskStatus) -> String {
+    format!("Cannot change task status from {:?} to {:?}", from, to)
+}
+
+// Quote a CSV field per RFC 4180 when it contains a delimiter, quote or line break,
+// doubling any embedded quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Position of two tasks in the manual order, ties broken by id
+fn manual_order(a: &Task, b: &Task) -> std::cmp::Ordering {
+    a.sort_order.total_cmp(&b.sort_order).then_with(|| a.id.cmp(&b.id))
+}
+
+// Current time as unix seconds
+fn now_secs() -> u64 {
+    unix_secs(std::time::SystemTime::now())
+}
+
+// Unix seconds for `time`, clamped to 0 for times before the epoch. A clock set before 1970
+// (seen in VMs and containers at boot) would otherwise make `duration_since` fail and panic
+// the process.
+fn unix_secs(time: std::time::SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Process that forwards HTTP requests to us
+const HTTP_SERVER_PROCESS: &str = "http-server:distro:sys";
+
+// The identity the current request acts as: the address of the process that sent it, checked by
+// the runtime. None for HTTP requests: `/api/tasks` is bound without login, so http-server
+// vouches for nobody and the caller is anonymous.
+fn caller_identity() -> Option<String> {
+    let source = hyperware_app_common::source();
+    let from_http = source.node == hyperware_process_lib::our().node
+        && source.process.to_string() == HTTP_SERVER_PROCESS;
+    (!from_http).then(|| source.to_string())
+}
+
+// Tell each of `assignees`' Task Manager about a task assigned to it, without waiting for the
+// replies. An assignee must be a full address (`node@process:package:publisher`) for a
+// notification to be sent; other values are treated as free-form names and skipped. Delivery
+// failures are only logged, so they never fail the create/update that triggered them.
+fn notify_assignees(task: &Task, assignees: &[String]) {
+    for assignee in assignees {
+        let Ok(address) = assignee.parse::<Address>() else {
+            hyperware_process_lib::logging::info!(
+                "Assignee {} of task {} is not a node/process address; skipping notification",
+                assignee,
+                task.id
+            );
+            continue;
+        };
+        
+        let task = task.clone();
+        hyperware_app_common::hyper! {
+            if !matches!(notify_assignment_remote_rpc(&address, task.clone()).await, SendResult::Success(_)) {
+                hyperware_process_lib::logging::warn!("Failed to notify assignee of task {}", task.id);
+            }
+        }
+    }
+}
+
+// Assignees as any version stored them: a list, or the single optional assignee used before
+// tasks could have several
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AssigneesRepr {
+    Many(Vec<String>),
+    One(Option<String>),
+}
+
+impl From<AssigneesRepr> for Vec<String> {
+    fn from(repr: AssigneesRepr) -> Self {
+        match repr {
+            AssigneesRepr::Many(assignees) => assignees,
+            AssigneesRepr::One(assignee) => assignee.into_iter().collect(),
+        }
+    }
+}
+
+// Deserialize an assignee list, migrating the old single `Option<String>` form
+fn deserialize_assignees<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    AssigneesRepr::deserialize(deserializer).map(Vec::from)
+}
+
+// Like `deserialize_assignees`, but a missing or null value means "no change"
+fn deserialize_optional_assignees<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error> {
+    Option::<AssigneesRepr>::deserialize(deserializer).map(|repr| repr.map(Vec::from))
+}
+
+// A string-keyed map as any version sent it: the list of `(key, value)` pairs matching the WIT
+// `list<tuple<string, _>>` caller-utils is generated from, or the JSON object used before
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PairsRepr<V> {
+    Pairs(Vec<(String, V)>),
+    Map(HashMap<String, V>),
+}
+
+// Serialize a string-keyed map as a list of `(key, value)` pairs, in key order
+fn serialize_pairs<V: Serialize, S: Serializer>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut pairs: Vec<(&String, &V)> = map.iter().collect();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+    pairs.serialize(serializer)
+}
+
+// Deserialize a string-keyed map from a list of pairs, migrating the old object form
+fn deserialize_pairs<'de, V: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<String, V>, D::Error> {
+    Ok(match PairsRepr::deserialize(deserializer)? {
+        PairsRepr::Pairs(pairs) => pairs.into_iter().collect(),
+        PairsRepr::Map(map) => map,
+    })
+}
+
+// Trim assignees, dropping empty and duplicate entries while keeping their order
+fn normalize_assignees(assignees: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(assignees.len());
+    for assignee in assignees {
+        let assignee = assignee.trim().to_string();
+        if !assignee.is_empty() && !normalized.contains(&assignee) {
+            normalized.push(assignee);
+        }
+    }
+    normalized
+}
+
+// Suspend the calling handler for `secs` seconds using the runtime timer.
+// The timer replies with an empty body, so the deserialization result is ignored.
+async fn sleep_secs(secs: u64) {
+    let timer_addr = Address::process("timer:distro:sys");
+    let _ = send::<serde_json::Value>(&TimerAction::SetTimer(secs * 1000), &timer_addr, secs + 1).await;
+}
+
+// Queue a `Tick` request to ourselves; the handler runs maintenance and re-arms itself
+fn request_tick() {
+    let _ = Request::to(hyperware_process_lib::our())
+        .body(json!({"Tick" : {}}).to_string().into_bytes())
+        .send();
+}
+
+/// Failure modes of calls to the storage process
+#[derive(Debug, Clone, PartialEq)]
+enum StorageError {
+    Timeout,
+    Offline,
+    Deserialize(String),
+}
+
+impl StorageError {
+    /// Timeouts and an offline storage process may recover on retry; a malformed reply won't
+    fn is_transient(&self) -> bool {
+        !matches!(self, StorageError::Deserialize(_))
+    }
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Timeout => write!(f, "storage process did not reply in time"),
+            StorageError::Offline => write!(f, "storage process is offline or unreachable"),
+            StorageError::Deserialize(e) => write!(f, "storage process sent an unreadable reply: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// Default number of attempts made for each storage call before giving up
+const STORAGE_ATTEMPTS: u32 = 3;
+
+/// Backoff before the first storage retry, doubling for each further retry, in seconds
+const STORAGE_RETRY_BACKOFF_SECS: u64 = 1;
+
+/// Upper bound on the backoff between storage retries, in seconds
+const MAX_STORAGE_RETRY_BACKOFF_SECS: u64 = 30;
+
+// Convert a storage RPC result into a `StorageError` on failure
+fn storage_result<T>(result: SendResult<T>) -> Result<T, StorageError> {
+    match result {
+        SendResult::Success(value) => Ok(value),
+        SendResult::Timeout => Err(StorageError::Timeout),
+        SendResult::Offline => Err(StorageError::Offline),
+        SendResult::DeserializationError(e) => Err(StorageError::Deserialize(e)),
+    }
+}
+
+// Run a storage call up to `attempts` times, retrying transient failures with an exponential backoff
+async fn with_storage_retry<T, F, Fut>(attempts: u32, call: F) -> Result<T, StorageError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = SendResult<T>>,
+{
+    retry_with_backoff(attempts, call, sleep_secs).await
+}
+
+// `with_storage_retry` with the backoff wait passed in as `sleep`, called with the seconds to wait
+async fn retry_with_backoff<T, F, Fut, S, SleepFut>(attempts: u32, mut call: F, mut sleep: S) -> Result<T, StorageError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = SendResult<T>>,
+    S: FnMut(u64) -> SleepFut,
+    SleepFut: Future<Output = ()>,
+{
+    let mut attempt = 1;
+    let mut backoff = STORAGE_RETRY_BACKOFF_SECS;
+    loop {
+        match storage_result(call().await) {
+            Err(e) if e.is_transient() && attempt < attempts => {
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_STORAGE_RETRY_BACKOFF_SECS);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+// Log a failed storage call: transient failures as warnings, unreadable replies as errors.
+// `operation` names what was attempted and which tasks, never their contents.
+fn log_storage_failure(operation: &str, error: &StorageError) {
+    if error.is_transient() {
+        hyperware_process_lib::logging::warn!("Storage failed to {}: {}", operation, error);
+    } else {
+        hyperware_process_lib::logging::error!("Storage failed to {}: {}", operation, error);
+    }
+}
+
+/// Timeout for the health check's storage ping, in seconds
+const STORAGE_PING_TIMEOUT_SECS: u64 = 2;
+
+/// Tasks per storage call when `sync_all_to_storage` writes everything
+const STORAGE_SYNC_BATCH_LEN: usize = 100;
+
+/// Storage process used when none has been configured, on this node
+const DEFAULT_STORAGE_PROCESS: &str = "task-storage:app:sys";
+
+/// Capability a storage process lists in reply to `GetCapabilities` when it takes packed tasks
+const STORAGE_PACKED_CAPABILITY: &str = "msgpack";
+
+/// The storage process to call, how many attempts each call gets and whether tasks are packed
+struct StorageTarget {
+    address: Address,
+    attempts: u32,
+    packed: bool,
+}
+
+// Encode tasks for the packed storage calls: MessagePack with structs as arrays of their fields,
+// in declaration order, then base64 so the body stays a JSON string
+fn pack_tasks<T: Serialize + ?Sized>(tasks: &T) -> Result<String, rmp_serde::encode::Error> {
+    rmp_serde::to_vec(tasks).map(|bytes| BASE64_STANDARD.encode(bytes))
+}
+
+// Decode tasks packed by `pack_tasks`
+fn unpack_tasks<T: serde::de::DeserializeOwned>(packed: &str) -> Result<T, StorageError> {
+    let bytes = BASE64_STANDARD
+        .decode(packed)
+        .map_err(|e| StorageError::Deserialize(e.to_string()))?;
+    rmp_serde::from_slice(&bytes).map_err(|e| StorageError::Deserialize(e.to_string()))
 }
 
 // Helper functions for communicating with other processes
-async fn store_task_in_storage(task: &Task) -> SendResult<bool> {
-    // Get the address of the storage process
-    let storage_addr = Address::process("task-storage:app:sys");
+async fn store_task_in_storage(storage: &StorageTarget, task: &Task) -> Result<bool, StorageError> {
+    if storage.packed {
+        match pack_tasks(task) {
+            Ok(packed) => {
+                return with_storage_retry(storage.attempts, || {
+                    add_task_packed_remote_rpc(&storage.address, packed.clone(), 5)
+                })
+                .await;
+            }
+            Err(e) => hyperware_process_lib::logging::warn!("Failed to pack task {}, sending JSON: {}", task.id, e),
+        }
+    }
     
     // Call the remote function to store the task
-    add_task_remote_rpc(&storage_addr, task.clone(), 5).await
+    with_storage_retry(storage.attempts, || {
+        add_task_remote_rpc(&storage.address, task.clone(), 5)
+    })
+    .await
+}
+
+async fn store_tasks_in_storage(storage: &StorageTarget, tasks: &[Task]) -> Result<bool, StorageError> {
+    if storage.packed {
+        match pack_tasks(tasks) {
+            Ok(packed) => {
+                return with_storage_retry(storage.attempts, || {
+                    add_tasks_packed_remote_rpc(&storage.address, packed.clone(), 5)
+                })
+                .await;
+            }
+            Err(e) => hyperware_process_lib::logging::warn!("Failed to pack {} tasks, sending JSON: {}", tasks.len(), e),
+        }
+    }
+    
+    // Call the remote function to store all tasks in one batch
+    with_storage_retry(storage.attempts, || {
+        add_tasks_remote_rpc(&storage.address, tasks.to_vec(), 5)
+    })
+    .await
 }
 
-async fn get_stored_tasks() -> Result<Vec<Task>, String> {
-    // Get the address of the storage process
-    let storage_addr = Address::process("task-storage:app:sys");
+async fn delete_task_in_storage(storage: &StorageTarget, task_id: &str) -> Result<bool, StorageError> {
+    // Call the remote function to delete the task
+    with_storage_retry(storage.attempts, || {
+        delete_task_remote_rpc(&storage.address, task_id.to_string(), 5)
+    })
+    .await
+}
+
+// Check the storage process answers, with a single short attempt. Any reply counts,
+// even one that can't be parsed, since it shows the process is up.
+async fn ping_storage(storage_addr: &Address) -> bool {
+    let result = get_tasks_by_status_remote_rpc(
+        storage_addr,
+        TaskStatus::Cancelled,
+        STORAGE_PING_TIMEOUT_SECS,
+    )
+    .await;
+    !matches!(storage_result(result), Err(e) if e.is_transient())
+}
+
+// Ask the storage process whether it takes packed tasks. Storage processes from before
+// `GetCapabilities` existed fail or time out, and get JSON.
+async fn storage_accepts_packed(storage_addr: &Address) -> bool {
+    let result = get_capabilities_remote_rpc(storage_addr, STORAGE_PING_TIMEOUT_SECS).await;
+    match storage_result(result) {
+        Ok(capabilities) => capabilities.iter().any(|capability| capability == STORAGE_PACKED_CAPABILITY),
+        Err(_) => false,
+    }
+}
+
+async fn get_stored_tasks(storage: &StorageTarget) -> Result<Vec<Task>, StorageError> {
+    if storage.packed {
+        let packed = with_storage_retry(storage.attempts, || {
+            get_all_tasks_packed_remote_rpc(&storage.address, 5)
+        })
+        .await?;
+        return unpack_tasks(&packed);
+    }
+    
+    // Call the remote function to get tasks of every status
+    with_storage_retry(storage.attempts, || get_all_tasks_remote_rpc(&storage.address, 5)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::time::{Duration, UNIX_EPOCH};
+    
+    // A new task with `title`, moved to `status` and assigned to `assignees`
+    fn task(title: &str, status: TaskStatus, assignees: &[&str]) -> Task {
+        let mut task = Task::from_request(NewTaskRequest { title: title.to_string(), ..Default::default() });
+        task.status = status;
+        task.assignees = assignees.iter().map(|assignee| assignee.to_string()).collect();
+        task
+    }
+    
+    #[test]
+    fn unix_secs_clamps_times_before_the_epoch() {
+        assert_eq!(unix_secs(UNIX_EPOCH - Duration::from_secs(1)), 0);
+        assert_eq!(unix_secs(UNIX_EPOCH), 0);
+        assert_eq!(unix_secs(UNIX_EPOCH + Duration::from_secs(42)), 42);
+    }
+    
+    #[test]
+    fn storage_retry_recovers_after_transient_failures() {
+        let calls = RefCell::new(0);
+        let sleeps = RefCell::new(Vec::new());
+        let result = futures::executor::block_on(retry_with_backoff(
+            STORAGE_ATTEMPTS,
+            || {
+                *calls.borrow_mut() += 1;
+                let result = if *calls.borrow() <= 2 { SendResult::Timeout } else { SendResult::Success(true) };
+                async move { result }
+            },
+            |secs| {
+                sleeps.borrow_mut().push(secs);
+                async {}
+            },
+        ));
+        assert_eq!(result, Ok(true));
+        assert_eq!(*calls.borrow(), 3);
+        assert_eq!(*sleeps.borrow(), vec![STORAGE_RETRY_BACKOFF_SECS, 2 * STORAGE_RETRY_BACKOFF_SECS]);
+    }
+    
+    #[test]
+    fn storage_retry_gives_up_after_the_last_attempt_or_a_bad_reply() {
+        let calls = RefCell::new(0);
+        let result: Result<bool, StorageError> = futures::executor::block_on(retry_with_backoff(
+            2,
+            || {
+                *calls.borrow_mut() += 1;
+                async { SendResult::Offline }
+            },
+            |_| async {},
+        ));
+        assert_eq!(result, Err(StorageError::Offline));
+        assert_eq!(*calls.borrow(), 2);
+        
+        *calls.borrow_mut() = 0;
+        let result: Result<bool, StorageError> = futures::executor::block_on(retry_with_backoff(
+            STORAGE_ATTEMPTS,
+            || {
+                *calls.borrow_mut() += 1;
+                async { SendResult::DeserializationError("garbage".to_string()) }
+            },
+            |_| async {},
+        ));
+        assert_eq!(result, Err(StorageError::Deserialize("garbage".to_string())));
+        assert_eq!(*calls.borrow(), 1);
+    }
+    
+    #[test]
+    fn storage_result_maps_every_send_result() {
+        assert_eq!(storage_result(SendResult::Success(7)), Ok(7));
+        assert_eq!(storage_result::<u32>(SendResult::Timeout), Err(StorageError::Timeout));
+        assert_eq!(storage_result::<u32>(SendResult::Offline), Err(StorageError::Offline));
+        assert_eq!(
+            storage_result::<u32>(SendResult::DeserializationError("bad".to_string())),
+            Err(StorageError::Deserialize("bad".to_string()))
+        );
+    }
+    
+    #[test]
+    fn storage_error_display() {
+        assert_eq!(StorageError::Timeout.to_string(), "storage process did not reply in time");
+        assert_eq!(StorageError::Offline.to_string(), "storage process is offline or unreachable");
+        assert_eq!(
+            StorageError::Deserialize("bad".to_string()).to_string(),
+            "storage process sent an unreadable reply: bad"
+        );
+    }
+    
+    #[test]
+    fn status_transitions_follow_the_table() {
+        use crate::TaskStatus::*;
+        let allowed = [(Pending, InProgress), (Pending, Cancelled), (InProgress, Completed), (InProgress, Cancelled)];
+        assert_eq!(STATUS_TRANSITIONS, &allowed);
+        
+        let statuses = [Pending, InProgress, Completed, Cancelled];
+        for from in &statuses {
+            for to in &statuses {
+                let expected = from == to || allowed.iter().any(|(a, b)| a == from && b == to);
+                assert_eq!(from.can_transition_to(to), expected, "{:?} -> {:?}", from, to);
+            }
+        }
+        for terminal in [Completed, Cancelled] {
+            for to in statuses.iter().filter(|to| **to != terminal) {
+                assert!(!terminal.can_transition_to(to), "{:?} should be terminal", terminal);
+            }
+        }
+    }
+    
+    #[test]
+    fn subscription_filter_matches_status_and_assignee() {
+        let task = task("Filtered", TaskStatus::InProgress, &["alice"]);
+        assert!(SubscriptionFilter::default().matches(&task));
+        
+        let by_status = |status| SubscriptionFilter { status: Some(status), assignee: None };
+        assert!(by_status(TaskStatus::InProgress).matches(&task));
+        assert!(!by_status(TaskStatus::Pending).matches(&task));
+        
+        let by_assignee = |assignee: &str| SubscriptionFilter { status: None, assignee: Some(assignee.to_string()) };
+        assert!(by_assignee("alice").matches(&task));
+        assert!(!by_assignee("bob").matches(&task));
+        
+        let both = SubscriptionFilter { status: Some(TaskStatus::InProgress), assignee: Some("bob".to_string()) };
+        assert!(!both.matches(&task));
+    }
     
-    // Call the remote function to get tasks
-    match get_tasks_by_status_remote_rpc(&storage_addr, TaskStatus::Pending, 5).await {
-        SendResult::Success(tasks) => Ok(tasks),
-        SendResult::Timeout => Err("Timeout connecting to storage".to_string()),
-        SendResult::Offline => Err("Storage service is offline".to_string()),
-        SendResult::DeserializationError(e) => Err(format!("Failed to deserialize tasks: {}", e)),
+    #[test]
+    fn rate_limit_engages_and_resets_with_the_window() {
+        let mut rate = RateState::new(1_000);
+        for _ in 0..WS_RATE_LIMIT {
+            assert!(rate.admit(1_000));
+        }
+        assert!(!rate.admit(1_000));
+        assert!(!rate.admit(1_000 + WS_RATE_WINDOW_SECS - 1));
+        
+        assert!(rate.admit(1_000 + WS_RATE_WINDOW_SECS));
+        assert_eq!(rate.count, 1);
+        assert_eq!(rate.window_start, 1_000 + WS_RATE_WINDOW_SECS);
+    }
+    
+    #[test]
+    fn snapshots_are_chunked_oldest_first() {
+        let total = 2 * WS_SNAPSHOT_CHUNK_TASKS + 50;
+        let tasks: Vec<Task> = (0..total)
+            .rev()
+            .map(|index| {
+                let mut task = task(&format!("Task {}", index), TaskStatus::Pending, &[]);
+                task.created_at = index as u64;
+                task
+            })
+            .collect();
+        
+        let events = snapshot_events(WS_PROTOCOL_VERSION, false, tasks.clone());
+        assert_eq!(events.len(), 4);
+        let mut reassembled = Vec::new();
+        for (index, event) in events[..3].iter().enumerate() {
+            let ServerWsEvent::SnapshotChunk { page, total_pages, tasks } = event else {
+                panic!("Expected a chunk, got {:?}", event);
+            };
+            assert_eq!((*page, *total_pages), (index as u64, 3));
+            assert!(tasks.len() <= WS_SNAPSHOT_CHUNK_TASKS);
+            reassembled.extend(tasks.iter().map(|task| task.created_at));
+        }
+        assert_eq!(reassembled, (0..total as u64).collect::<Vec<_>>());
+        assert!(matches!(events[3], ServerWsEvent::SnapshotComplete { total: sent } if sent == total as u64));
+        
+        let legacy = snapshot_events(0, false, tasks);
+        assert!(matches!(&legacy[..], [ServerWsEvent::Snapshot(all)] if all.len() == total));
     }
-}
\ No newline at end of file
+}
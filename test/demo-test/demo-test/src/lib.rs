@@ -3,10 +3,41 @@ mod tester_lib;
 use caller_utils::client::*;
 use caller_utils::curator::*;
 use caller_utils::indexer::*;
+use caller_utils::task_manager::*;
+use caller_utils::{
+    AssigneeFormat, ImportConflict, MatchField, NewTaskRequest, RecurrenceRule, SortField, SortSpec, TaskError,
+    TaskEventKind, TaskPriority, TaskStatus, TaskStatusUpdateRequest, TaskFilter, TaskTemplate, TaskUpdateRequest,
+    TasksSince,
+};
 use hyperware_app_common::SendResult;
 use tester_lib::*;
 use std::collections::HashMap;
 
+/// Base URL of the Task Manager HTTP API on the test node
+const TASK_MANAGER_URL: &str = "http://localhost:8080/task-manager:task-manager:uncentered.os";
+
+fn task_manager_address() -> Address {
+    ("indexer.os", "task-manager", "task-manager", "uncentered.os").into()
+}
+
+/// Build a minimal task creation request with the given title
+fn new_task(title: &str) -> NewTaskRequest {
+    NewTaskRequest {
+        title: title.to_string(),
+        description: String::new(),
+        assignees: Vec::new(),
+        priority: TaskPriority::Medium,
+        due_at: None,
+        tags: Vec::new(),
+        parent_id: None,
+        recurrence: None,
+        idempotency_key: None,
+        metadata: Vec::new(),
+        estimate_secs: None,
+        correlation_id: None,
+    }
+}
+
 async_test_suite!(
     "test-app-framework-demo-uncentered-dot-os-v0",
 
@@ -197,4 +228,3204 @@ async_test_suite!(
         
         Ok(())
     },
+
+    // Task manager tests
+    test_flush_state: async {
+        let address = task_manager_address();
+        
+        // Mutate state, then force it to be persisted
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Flush Test")),
+            "Failed to create task before flush"
+        ).await?;
+        if !created.success {
+            fail!("create_task did not succeed before flush");
+        }
+        
+        let flushed = test_remote_call(
+            flush_state_local_rpc(&address),
+            true,
+            "flush_state did not persist state"
+        ).await?;
+        print_to_terminal(0, &format!("flush_state_local_rpc result: {}", flushed));
+        
+        // The saved state decodes as the framework would restore it, and holds the new task
+        let Some(task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        let Some(saved_ids) = expect_success(get_saved_task_ids_local_rpc(&address), "Failed to read saved state").await? else {
+            fail!("No decodable state was saved");
+        };
+        if !saved_ids.contains(&task.id) {
+            fail!("The flushed state should contain the task created before the flush");
+        }
+        let stats = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        if saved_ids.len() as u64 != stats.total_tasks {
+            fail!(format!("Saved {} tasks, but {} are held in memory", saved_ids.len(), stats.total_tasks));
+        }
+        
+        Ok(())
+    },
+
+    test_status_transitions: async {
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Transition Test")),
+            "Failed to create task"
+        ).await?;
+        let task_id = created.task.map(|task| task.id).unwrap_or_default();
+        
+        // (new status, expected success) along Pending -> InProgress -> Completed and back
+        let steps = [
+            (TaskStatus::Completed, false),
+            (TaskStatus::InProgress, true),
+            (TaskStatus::Pending, false),
+            (TaskStatus::Completed, true),
+            (TaskStatus::Pending, false),
+            (TaskStatus::Cancelled, false),
+        ];
+        for (new_status, allowed) in steps {
+            let response = expect_success(
+                update_task_status_http_rpc(
+                    TASK_MANAGER_URL,
+                    TaskStatusUpdateRequest {
+                        task_id: task_id.clone(),
+                        new_status: new_status.clone(),
+                        expected_version: None,
+                        correlation_id: None,
+                    },
+                ),
+                "Failed to update task status"
+            ).await?;
+            print_to_terminal(0, &format!("Transition to {:?}: {}", new_status, response.message));
+            if response.success != allowed {
+                fail!(format!("Transition to {:?} should have success={}", new_status, allowed));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_endpoint_statistics: async {
+        let address = task_manager_address();
+        
+        let _ = expect_success(reset_statistics_local_rpc(&address), "Failed to reset statistics").await?;
+        let _ = get_task_http_rpc(TASK_MANAGER_URL, "missing-task".to_string()).await;
+        let _ = get_task_http_rpc(TASK_MANAGER_URL, "missing-task".to_string()).await;
+        let _ = get_all_tasks_http_rpc(TASK_MANAGER_URL).await;
+        
+        let stats = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        let hits: HashMap<String, u64> = stats.endpoint_hits.into_iter().collect();
+        print_to_terminal(0, &format!("Endpoint hits: {:?}", hits));
+        
+        if hits.get("get_task") != Some(&2) || hits.get("get_all_tasks") != Some(&1) {
+            fail!("Per-endpoint hit counts don't reflect the calls made");
+        }
+        
+        Ok(())
+    },
+
+    test_new_task_validation: async {
+        // Empty and oversized fields are rejected
+        let mut oversized_description = new_task("Oversized Description");
+        oversized_description.description = "x".repeat(8193);
+        let rejected = [
+            ("empty title", new_task("   ")),
+            ("oversized title", new_task(&"x".repeat(257))),
+            ("oversized description", oversized_description),
+        ];
+        for (case, req) in rejected {
+            let response = expect_success(
+                create_task_http_rpc(TASK_MANAGER_URL, req),
+                "Failed to call create_task"
+            ).await?;
+            if response.success || response.task.is_some() {
+                fail!(format!("Task with {} should have been rejected", case));
+            }
+            print_to_terminal(0, &format!("Rejected {}: {}", case, response.message));
+        }
+        
+        // Surrounding whitespace is trimmed
+        let mut padded = new_task("  Padded Title  ");
+        padded.description = "\tPadded description\n".to_string();
+        let response = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, padded),
+            "Failed to create padded task"
+        ).await?;
+        match response.task {
+            Some(task) if task.title == "Padded Title" && task.description == "Padded description" => {}
+            other => {
+                fail!(format!("Expected trimmed title and description, got {:?}", other));
+            }
+        }
+        
+        // update_task holds titles and descriptions to the same limits, and trims them too
+        let Some(task) = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Updated Validation Task")),
+            "Failed to create task"
+        ).await?.task else {
+            fail!("Created task missing from response");
+        };
+        let update = |title: Option<String>, description: Option<String>| TaskUpdateRequest {
+            task_id: task.id.clone(),
+            title,
+            description,
+            assignees: None,
+            new_status: None,
+            parent_id: None,
+            expected_version: None,
+            correlation_id: None,
+        };
+        let rejected = [
+            ("empty title", update(Some("   ".to_string()), None)),
+            ("oversized title", update(Some("x".repeat(257)), None)),
+            ("oversized description", update(None, Some("x".repeat(8193)))),
+        ];
+        for (case, req) in rejected {
+            let response = expect_success(update_task_http_rpc(TASK_MANAGER_URL, req), "Failed to call update_task").await?;
+            if response.success || response.status_code != 400 {
+                fail!(format!("Update with {} should have been rejected", case));
+            }
+        }
+        let response = expect_success(
+            update_task_http_rpc(TASK_MANAGER_URL, update(Some("  Updated Title  ".to_string()), Some(" Trimmed\n".to_string()))),
+            "Failed to update task"
+        ).await?;
+        match response.task {
+            Some(task) if task.title == "Updated Title" && task.description == "Trimmed" => {}
+            other => {
+                fail!(format!("Expected trimmed title and description after update, got {:?}", other));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_subtasks: async {
+        let parent = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Parent Task")),
+            "Failed to create parent task"
+        ).await?;
+        let parent_id = parent.task.map(|task| task.id).unwrap_or_default();
+        
+        let mut child_req = new_task("Child Task");
+        child_req.parent_id = Some(parent_id.clone());
+        let child = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, child_req),
+            "Failed to create child task"
+        ).await?;
+        let child_id = child.task.map(|task| task.id).unwrap_or_default();
+        
+        // A subtask of a missing parent is rejected
+        let mut orphan_req = new_task("Orphan Task");
+        orphan_req.parent_id = Some("missing-parent".to_string());
+        let orphan = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, orphan_req),
+            "Failed to call create_task"
+        ).await?;
+        if orphan.success {
+            fail!("Task with a missing parent should be rejected");
+        }
+        
+        // Moving the parent under its own child would create a cycle
+        let cycle = expect_success(
+            update_task_http_rpc(TASK_MANAGER_URL, TaskUpdateRequest {
+                task_id: parent_id.clone(),
+                title: None,
+                description: None,
+                assignees: None,
+                new_status: None,
+                parent_id: Some(child_id.clone()),
+                expected_version: None,
+                correlation_id: None,
+            }),
+            "Failed to call update_task"
+        ).await?;
+        if cycle.success {
+            fail!("Reparenting a task under its own subtask should be rejected");
+        }
+        
+        // Deleting a parent is blocked while it has subtasks
+        let blocked = expect_success(
+            delete_task_http_rpc(TASK_MANAGER_URL, parent_id.clone()),
+            "Failed to call delete_task"
+        ).await?;
+        if blocked.success {
+            fail!("Deleting a task with subtasks should be blocked");
+        }
+        
+        let subtasks = expect_success(
+            get_subtasks_http_rpc(TASK_MANAGER_URL, parent_id.clone()),
+            "Failed to get subtasks"
+        ).await?;
+        if subtasks.len() != 1 || subtasks[0].id != child_id {
+            fail!(format!("Expected exactly the child subtask, got {:?}", subtasks));
+        }
+        
+        // Once the child is gone the parent can be deleted
+        let _ = delete_task_http_rpc(TASK_MANAGER_URL, child_id).await;
+        let deleted = expect_success(
+            delete_task_http_rpc(TASK_MANAGER_URL, parent_id),
+            "Failed to call delete_task"
+        ).await?;
+        if !deleted.success {
+            fail!("Deleting a task without subtasks should succeed");
+        }
+        
+        Ok(())
+    },
+
+    test_response_status_codes: async {
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Status Code Task")),
+            "Failed to create task"
+        ).await?;
+        let task_id = created.task.map(|t| t.id).unwrap_or_default();
+        
+        let illegal = TaskStatusUpdateRequest {
+            task_id: task_id.clone(),
+            new_status: TaskStatus::Completed,
+            expected_version: None,
+            correlation_id: None,
+        };
+        let cases = [
+            ("existing task", get_task_http_rpc(TASK_MANAGER_URL, task_id.clone()).await, 200),
+            ("unknown task", get_task_http_rpc(TASK_MANAGER_URL, "missing-task".to_string()).await, 404),
+            ("invalid task", create_task_http_rpc(TASK_MANAGER_URL, new_task("   ")).await, 400),
+            ("illegal transition", update_task_status_http_rpc(TASK_MANAGER_URL, illegal).await, 409),
+        ];
+        for (case, result, expected) in cases {
+            let response = expect_success(async { result }, "Failed to call task manager").await?;
+            if response.status_code != expected {
+                fail!(format!("{}: expected status {}, got {}", case, expected, response.status_code));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_version_conflict: async {
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Contended Task")),
+            "Failed to create task"
+        ).await?;
+        let Some(task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        
+        // Both clients read the same version; only the first update may land
+        let update = |new_status: TaskStatus| TaskStatusUpdateRequest {
+            task_id: task.id.clone(),
+            new_status,
+            expected_version: Some(task.version),
+            correlation_id: None,
+        };
+        let first = expect_success(
+            update_task_status_http_rpc(TASK_MANAGER_URL, update(TaskStatus::InProgress)),
+            "Failed to call update_task_status"
+        ).await?;
+        let second = expect_success(
+            update_task_status_http_rpc(TASK_MANAGER_URL, update(TaskStatus::Cancelled)),
+            "Failed to call update_task_status"
+        ).await?;
+        print_to_terminal(0, &format!("Second update: {}", second.message));
+        
+        if !first.success || first.task.as_ref().map(|t| t.version) != Some(task.version + 1) {
+            fail!("First update should succeed and bump the version");
+        }
+        if second.success || second.status_code != 409 {
+            fail!("Second update against a stale version should be rejected");
+        }
+        match second.task {
+            Some(current) if current.status == TaskStatus::InProgress => {}
+            other => {
+                fail!(format!("Task should keep the first update, got {:?}", other));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_storage_address: async {
+        let address = task_manager_address();
+        
+        let previous = expect_success(
+            get_storage_address_local_rpc(&address),
+            "Failed to get storage address"
+        ).await?;
+        print_to_terminal(0, &format!("Storage address: {}", previous));
+        
+        let accepted = expect_success(
+            set_storage_address_local_rpc(&address, "not an address".to_string()),
+            "Failed to call set_storage_address"
+        ).await?;
+        if accepted {
+            fail!("Invalid storage address should be rejected");
+        }
+        
+        test_remote_call(
+            get_storage_address_local_rpc(&address),
+            previous,
+            "Rejected storage address should leave the previous one intact"
+        ).await?;
+        
+        Ok(())
+    },
+
+    test_task_counts: async {
+        let before = expect_success(get_counts_http_rpc(TASK_MANAGER_URL), "Failed to get counts").await?;
+        let _ = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Counted Task")),
+            "Failed to create task"
+        ).await?;
+        let after = expect_success(get_counts_http_rpc(TASK_MANAGER_URL), "Failed to get counts").await?;
+        print_to_terminal(0, &format!("Counts: {:?}", after));
+        
+        if after.total != before.total + 1 || after.pending != before.pending + 1 {
+            fail!("New pending task should be counted once");
+        }
+        if after.total != after.pending + after.in_progress + after.completed + after.cancelled {
+            fail!("Per-status counts should add up to the total");
+        }
+        
+        Ok(())
+    },
+
+    test_tasks_in_range: async {
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Ranged Task")),
+            "Failed to create task"
+        ).await?;
+        let Some(task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        
+        // Bounds are inclusive, and the remote stub sees the same slice
+        let in_range = expect_success(
+            get_tasks_in_range_http_rpc(TASK_MANAGER_URL, task.created_at, task.created_at),
+            "Failed to get tasks in range"
+        ).await?;
+        if !in_range.iter().any(|t| t.id == task.id) {
+            fail!("Task created at the range bounds should be included");
+        }
+        if in_range.windows(2).any(|pair| pair[0].created_at > pair[1].created_at) {
+            fail!("Tasks in range should be sorted by creation time");
+        }
+        let remote = expect_success(
+            get_tasks_in_range_remote_rpc(&task_manager_address(), task.created_at, task.created_at),
+            "Failed to get tasks in range remotely"
+        ).await?;
+        if remote.len() != in_range.len() {
+            fail!("Remote and HTTP range queries should agree");
+        }
+        
+        test_remote_call(
+            get_tasks_in_range_http_rpc(TASK_MANAGER_URL, task.created_at + 1, task.created_at),
+            Vec::new(),
+            "Inverted range should match nothing"
+        ).await?;
+        
+        Ok(())
+    },
+
+    test_status_index: async {
+        let address = task_manager_address();
+        
+        // Exercise every index update path: create, status change, field update and delete
+        let mut ids = Vec::new();
+        for title in ["Indexed Task 1", "Indexed Task 2"] {
+            let created = expect_success(
+                create_task_http_rpc(TASK_MANAGER_URL, new_task(title)),
+                "Failed to create task"
+            ).await?;
+            ids.push(created.task.map(|t| t.id).unwrap_or_default());
+        }
+        let _ = expect_success(
+            update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                task_id: ids[0].clone(),
+                new_status: TaskStatus::InProgress,
+                expected_version: None,
+                correlation_id: None,
+            }),
+            "Failed to update task status"
+        ).await?;
+        let _ = expect_success(
+            update_task_http_rpc(TASK_MANAGER_URL, TaskUpdateRequest {
+                task_id: ids[0].clone(),
+                title: None,
+                description: None,
+                assignees: None,
+                new_status: Some(TaskStatus::Completed),
+                parent_id: None,
+                expected_version: None,
+                correlation_id: None,
+            }),
+            "Failed to update task"
+        ).await?;
+        let _ = expect_success(delete_task_http_rpc(TASK_MANAGER_URL, ids[1].clone()), "Failed to delete task").await?;
+        
+        test_remote_call(
+            check_status_index_local_rpc(&address),
+            true,
+            "Status index should match a rebuild from tasks"
+        ).await?;
+        
+        let completed = expect_success(
+            get_tasks_by_status_local_rpc(&address, TaskStatus::Completed),
+            "Failed to get completed tasks"
+        ).await?;
+        let pending = expect_success(
+            get_tasks_by_status_local_rpc(&address, TaskStatus::Pending),
+            "Failed to get pending tasks"
+        ).await?;
+        if !completed.iter().any(|t| t.id == ids[0]) || pending.iter().any(|t| t.id == ids[0] || t.id == ids[1]) {
+            fail!("Status queries should reflect the updated and deleted tasks");
+        }
+        
+        Ok(())
+    },
+
+    test_recurring_task: async {
+        let title = "Recurring Chore";
+        let mut req = new_task(title);
+        req.recurrence = Some(RecurrenceRule { interval_secs: 7 * 24 * 60 * 60 });
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, req),
+            "Failed to create recurring task"
+        ).await?;
+        let task_id = created.task.map(|t| t.id).unwrap_or_default();
+        
+        // Completing twice (the second is a same-status no-op) must regenerate only once
+        for new_status in [TaskStatus::InProgress, TaskStatus::Completed, TaskStatus::Completed] {
+            let _ = expect_success(
+                update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                    task_id: task_id.clone(),
+                    new_status,
+                    expected_version: None,
+                    correlation_id: None,
+                }),
+                "Failed to update task status"
+            ).await?;
+        }
+        
+        let matches = expect_success(
+            search_tasks_http_rpc(TASK_MANAGER_URL, title.to_string(), false),
+            "Failed to search tasks"
+        ).await?;
+        let successors: Vec<_> = matches
+            .iter()
+            .map(|result| &result.task)
+            .filter(|t| t.title == title && t.id != task_id)
+            .collect();
+        print_to_terminal(0, &format!("Successors: {:?}", successors));
+        
+        match successors.as_slice() {
+            [next] if next.status == TaskStatus::Pending
+                && next.due_at == Some(next.created_at + 7 * 24 * 60 * 60)
+                && next.recurrence.is_some() => {}
+            _ => {
+                fail!("Completing a recurring task should produce exactly one pending successor");
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_reassign_task: async {
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Reassigned Task")),
+            "Failed to create task"
+        ).await?;
+        let task_id = created.task.map(|t| t.id).unwrap_or_default();
+        
+        // Assign, then unassign
+        for assignee in [Some("alice".to_string()), None] {
+            let response = expect_success(
+                reassign_task_http_rpc(TASK_MANAGER_URL, task_id.clone(), assignee.clone()),
+                "Failed to reassign task"
+            ).await?;
+            let expected: Vec<String> = assignee.clone().into_iter().collect();
+            if !response.success || response.task.map(|t| t.assignees) != Some(expected) {
+                fail!(format!("Task should be assigned to {:?}", assignee));
+            }
+        }
+        
+        let history = expect_success(
+            get_task_history_http_rpc(TASK_MANAGER_URL, task_id.clone()),
+            "Failed to get task history"
+        ).await?;
+        let reassignments = history
+            .iter()
+            .filter(|event| matches!(event.kind, TaskEventKind::Reassigned(_)))
+            .count();
+        if reassignments != 2 {
+            fail!(format!("Expected 2 reassignment events, got {}", reassignments));
+        }
+        
+        let missing = expect_success(
+            reassign_task_http_rpc(TASK_MANAGER_URL, "missing-task".to_string(), None),
+            "Failed to call reassign_task"
+        ).await?;
+        if missing.success {
+            fail!("Reassigning an unknown task should fail");
+        }
+        
+        Ok(())
+    },
+
+    test_bulk_dry_run: async {
+        let before = expect_success(get_counts_http_rpc(TASK_MANAGER_URL), "Failed to get counts").await?;
+        
+        let batch = vec![new_task("Dry Run Task"), new_task("   ")];
+        let report = expect_success(
+            validate_tasks_bulk_http_rpc(TASK_MANAGER_URL, batch),
+            "Failed to validate batch"
+        ).await?;
+        print_to_terminal(0, &format!("Dry run failures: {:?}", report.failed));
+        if report.created.len() != 1 || report.failed.len() != 1 {
+            fail!("Dry run should report one valid and one invalid task");
+        }
+        
+        let after = expect_success(get_counts_http_rpc(TASK_MANAGER_URL), "Failed to get counts").await?;
+        if after.total != before.total {
+            fail!("Dry run must not create tasks");
+        }
+        test_remote_call(
+            search_tasks_http_rpc(TASK_MANAGER_URL, "Dry Run Task".to_string(), false),
+            Vec::new(),
+            "Dry run task should not be stored"
+        ).await?;
+        
+        Ok(())
+    },
+
+    test_export_tasks_csv: async {
+        let mut req = new_task("CSV Task");
+        req.description = "Buy milk, eggs, and \"fresh\" bread\nfrom the market".to_string();
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, req),
+            "Failed to create task"
+        ).await?;
+        let task_id = created.task.map(|t| t.id).unwrap_or_default();
+        
+        let csv = expect_success(export_tasks_csv_http_rpc(TASK_MANAGER_URL), "Failed to export CSV").await?;
+        if !csv.starts_with("id,title,description,status,created_at,assignees\r\n") {
+            fail!("CSV export should start with the header row");
+        }
+        
+        // Commas, quotes and the line break stay inside one quoted field; no assignee is an empty field
+        let expected_prefix = format!(
+            "{},CSV Task,\"Buy milk, eggs, and \"\"fresh\"\" bread\nfrom the market\",Pending,",
+            task_id
+        );
+        let Some(row_start) = csv.find(&expected_prefix) else {
+            fail!(format!("CSV export should escape the description, got {:?}", csv));
+        };
+        if !csv[row_start..].split("\r\n").next().is_some_and(|row| row.ends_with(',')) {
+            fail!("Unassigned task should export an empty assignees field");
+        }
+        
+        Ok(())
+    },
+
+    test_import_tasks: async {
+        let address = task_manager_address();
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Imported Task")),
+            "Failed to create task"
+        ).await?;
+        let Some(task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        let before = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        
+        // Re-importing an existing task is skipped...
+        let skipped = expect_success(
+            import_tasks_http_rpc(TASK_MANAGER_URL, vec![task.clone()], ImportConflict::Skip),
+            "Failed to import tasks"
+        ).await?;
+        if !skipped.created.is_empty() || skipped.failed.len() != 1 {
+            fail!("Duplicate task should be skipped");
+        }
+        
+        // ...or imported under a fresh id, while invalid tasks are reported
+        let mut untitled = task.clone();
+        untitled.title = "  ".to_string();
+        let imported = expect_success(
+            import_tasks_http_rpc(TASK_MANAGER_URL, vec![task.clone(), untitled], ImportConflict::RegenerateId),
+            "Failed to import tasks"
+        ).await?;
+        print_to_terminal(0, &format!("Import failures: {:?}", imported.failed));
+        match imported.created.as_slice() {
+            [copy] if copy.id != task.id && copy.title == task.title => {}
+            _ => {
+                fail!("Duplicate task should be imported once under a new id");
+            }
+        }
+        if imported.failed.len() != 1 {
+            fail!("Task without a title should be rejected");
+        }
+        
+        let after = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        if after.creation_count != before.creation_count || after.request_count < before.request_count {
+            fail!("Importing should leave the analytics counters alone");
+        }
+        
+        Ok(())
+    },
+
+    test_health: async {
+        let health = expect_success(health_http_rpc(TASK_MANAGER_URL), "Failed to get health").await?;
+        print_to_terminal(0, &format!("Health: {:?}", health));
+        
+        // Storage may or may not be running in the test environment; only check consistency
+        let stats = expect_success(
+            get_statistics_local_rpc(&task_manager_address()),
+            "Failed to get statistics"
+        ).await?;
+        if health.ok != health.storage_reachable || health.task_count != stats.total_tasks {
+            fail!("Health report is inconsistent with the task manager's state");
+        }
+        
+        Ok(())
+    },
+
+    test_single_welcome_task: async {
+        // Restarting the task manager isn't possible from the suite, so check the observable
+        // outcome: the welcome task is only seeded when there are no tasks, hence never twice
+        let matches = expect_success(
+            search_tasks_http_rpc(TASK_MANAGER_URL, "Welcome Task".to_string(), false),
+            "Failed to search tasks"
+        ).await?;
+        let welcome_tasks = matches.iter().filter(|r| r.task.title == "Welcome Task").count();
+        if welcome_tasks > 1 {
+            fail!(format!("Expected at most one welcome task, found {}", welcome_tasks));
+        }
+        
+        expect_success(
+            set_seed_welcome_task_local_rpc(&task_manager_address(), false),
+            "Failed to disable the welcome task"
+        ).await?;
+        expect_success(
+            set_seed_welcome_task_local_rpc(&task_manager_address(), true),
+            "Failed to re-enable the welcome task"
+        ).await?;
+        
+        Ok(())
+    },
+
+    test_pending_storage_sync: async {
+        let address = task_manager_address();
+        let storage = expect_success(get_storage_address_local_rpc(&address), "Failed to get storage address").await?;
+        
+        // Point at a storage process that doesn't exist, with a single attempt to keep the test fast
+        expect_success(set_storage_attempts_local_rpc(&address, 1), "Failed to set storage attempts").await?;
+        expect_success(
+            set_storage_address_local_rpc(&address, "indexer.os@missing-storage:app:sys".to_string()),
+            "Failed to set storage address"
+        ).await?;
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Unsynced Task")),
+            "Failed to create task"
+        ).await?;
+        let task_id = created.task.map(|t| t.id).unwrap_or_default();
+        if created.storage_status {
+            fail!("Storing to a missing storage process should fail");
+        }
+        let pending = expect_success(sync_pending_storage_local_rpc(&address), "Failed to sync storage").await?;
+        if !pending.contains(&task_id) {
+            fail!("Task that failed to store should stay queued while storage is unreachable");
+        }
+        
+        // Restore the real storage process; the sweep drains the queue once storage answers
+        expect_success(set_storage_address_local_rpc(&address, storage), "Failed to restore storage address").await?;
+        expect_success(set_storage_attempts_local_rpc(&address, 3), "Failed to restore storage attempts").await?;
+        let health = expect_success(health_http_rpc(TASK_MANAGER_URL), "Failed to get health").await?;
+        let pending = expect_success(sync_pending_storage_local_rpc(&address), "Failed to sync storage").await?;
+        print_to_terminal(0, &format!("Storage reachable: {}, pending: {:?}", health.storage_reachable, pending));
+        if health.storage_reachable && !pending.is_empty() {
+            fail!("Pending queue should drain once storage is reachable");
+        }
+        
+        Ok(())
+    },
+
+    test_reconcile_with_storage: async {
+        let address = task_manager_address();
+        let health = expect_success(health_http_rpc(TASK_MANAGER_URL), "Failed to get health").await?;
+        if !health.storage_reachable {
+            print_to_terminal(0, "Storage not running; skipping reconcile test");
+            return Ok(());
+        }
+        let storage = expect_success(get_storage_address_local_rpc(&address), "Failed to get storage address").await?;
+        let Ok(storage_address) = storage.parse::<Address>() else {
+            fail!(format!("Storage address {} doesn't parse", storage));
+        };
+        
+        // In both places, then changed behind the task manager's back: conflicting
+        let synced = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Reconciled Task")),
+            "Failed to create task"
+        ).await?;
+        let Some(synced) = synced.task else {
+            fail!("Created task missing from response");
+        };
+        let mut changed = synced.clone();
+        changed.title = "Changed In Storage".to_string();
+        changed.version += 1;
+        
+        // Only in storage
+        let mut stored_only = synced.clone();
+        stored_only.id = format!("{}-storage-only", synced.id);
+        for task in [changed, stored_only.clone()] {
+            expect_success(
+                caller_utils::task_storage::add_task_remote_rpc(&storage_address, task, 5),
+                "Failed to write to storage"
+            ).await?;
+        }
+        
+        // Only local: created while storage is unreachable
+        expect_success(set_storage_attempts_local_rpc(&address, 1), "Failed to set storage attempts").await?;
+        expect_success(
+            set_storage_address_local_rpc(&address, "indexer.os@missing-storage:app:sys".to_string()),
+            "Failed to set storage address"
+        ).await?;
+        let local_only = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Local Only Task")),
+            "Failed to create task"
+        ).await?;
+        let local_only_id = local_only.task.map(|t| t.id).unwrap_or_default();
+        expect_success(set_storage_address_local_rpc(&address, storage), "Failed to restore storage address").await?;
+        expect_success(set_storage_attempts_local_rpc(&address, 3), "Failed to restore storage attempts").await?;
+        
+        let report = expect_success(reconcile_with_storage_local_rpc(&address), "Failed to reconcile").await?;
+        print_to_terminal(0, &format!("Reconcile report: {:?}", report));
+        if report.storage_error.is_some()
+            || !report.only_local.contains(&local_only_id)
+            || !report.only_storage.contains(&stored_only.id)
+            || !report.conflicting.contains(&synced.id)
+        {
+            fail!("Reconcile report miscategorized the divergent tasks");
+        }
+        
+        Ok(())
+    },
+
+    test_assignee_workload: async {
+        // (assignee, final status) per task; bob only has completed work
+        let plan = [
+            ("workload-alice", TaskStatus::Pending),
+            ("workload-alice", TaskStatus::Pending),
+            ("workload-alice", TaskStatus::InProgress),
+            ("workload-bob", TaskStatus::Completed),
+        ];
+        for (assignee, status) in plan {
+            let mut req = new_task("Workload Task");
+            req.assignees = vec![assignee.to_string()];
+            let created = expect_success(
+                create_task_http_rpc(TASK_MANAGER_URL, req),
+                "Failed to create task"
+            ).await?;
+            let task_id = created.task.map(|t| t.id).unwrap_or_default();
+            let path: &[TaskStatus] = match status {
+                TaskStatus::InProgress => &[TaskStatus::InProgress],
+                TaskStatus::Completed => &[TaskStatus::InProgress, TaskStatus::Completed],
+                _ => &[],
+            };
+            for new_status in path {
+                expect_success(
+                    update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                        task_id: task_id.clone(),
+                        new_status: new_status.clone(),
+                        expected_version: None,
+                        correlation_id: None,
+                    }),
+                    "Failed to update task status"
+                ).await?;
+            }
+        }
+        
+        let local = expect_success(
+            get_assignee_workload_local_rpc(&task_manager_address()),
+            "Failed to get workload"
+        ).await?;
+        let http = expect_success(get_assignee_workload_http_rpc(TASK_MANAGER_URL), "Failed to get workload").await?;
+        for workload in [local, http] {
+            let workload: HashMap<String, _> = workload.into_iter().collect();
+            let counts = |name: &str| workload.get(name).map(|s| (s.pending, s.in_progress, s.completed));
+            if counts("workload-alice") != Some((2, 1, 0)) || counts("workload-bob") != Some((0, 0, 1)) {
+                fail!(format!("Unexpected workload: {:?}", workload));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_clear_completed: async {
+        // One task left in each status
+        let paths: [&[TaskStatus]; 4] = [
+            &[],
+            &[TaskStatus::InProgress],
+            &[TaskStatus::InProgress, TaskStatus::Completed],
+            &[TaskStatus::Cancelled],
+        ];
+        let mut ids = Vec::new();
+        for path in paths {
+            let created = expect_success(
+                create_task_http_rpc(TASK_MANAGER_URL, new_task("Tidy Task")),
+                "Failed to create task"
+            ).await?;
+            let task_id = created.task.map(|t| t.id).unwrap_or_default();
+            for new_status in path {
+                expect_success(
+                    update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                        task_id: task_id.clone(),
+                        new_status: new_status.clone(),
+                        expected_version: None,
+                        correlation_id: None,
+                    }),
+                    "Failed to update task status"
+                ).await?;
+            }
+            ids.push(task_id);
+        }
+        
+        let response = expect_success(clear_completed_http_rpc(TASK_MANAGER_URL), "Failed to clear completed").await?;
+        print_to_terminal(0, &format!("Cleared {} tasks", response.count));
+        if !response.deleted.contains(&ids[2]) || response.count != response.deleted.len() as u64 {
+            fail!("Completed task should be reported as deleted");
+        }
+        
+        let counts = expect_success(get_counts_http_rpc(TASK_MANAGER_URL), "Failed to get counts").await?;
+        if counts.completed != 0 {
+            fail!("No completed tasks should remain");
+        }
+        for (index, task_id) in ids.iter().enumerate() {
+            let found = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task_id.clone()), "Failed to get task").await?;
+            if found.success == (index == 2) {
+                fail!(format!("Task {} should {}", index, if index == 2 { "be gone" } else { "remain" }));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_idempotent_create: async {
+        let title = "Idempotent Task";
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let mut req = new_task(title);
+            req.idempotency_key = Some("idempotent-task-key".to_string());
+            let response = expect_success(
+                create_task_http_rpc(TASK_MANAGER_URL, req),
+                "Failed to create task"
+            ).await?;
+            if !response.success {
+                fail!(format!("Create with idempotency key failed: {}", response.message));
+            }
+            ids.push(response.task.map(|t| t.id).unwrap_or_default());
+        }
+        if ids[0] != ids[1] {
+            fail!("Retried create should return the original task");
+        }
+        
+        let matches = expect_success(
+            search_tasks_http_rpc(TASK_MANAGER_URL, title.to_string(), false),
+            "Failed to search tasks"
+        ).await?;
+        if matches.iter().filter(|r| r.task.title == title).count() != 1 {
+            fail!("Only one task should exist for the idempotency key");
+        }
+        
+        Ok(())
+    },
+
+    test_prepare_shutdown: async {
+        // Restarting isn't possible from the suite; check the admin call saves successfully
+        test_remote_call(
+            prepare_shutdown_local_rpc(&task_manager_address()),
+            true,
+            "prepare_shutdown should persist state"
+        ).await?;
+        
+        Ok(())
+    },
+
+    test_query_tasks: async {
+        // Only the first task matches every criterion
+        let variants = [
+            (TaskPriority::High, Some("query-carol")),
+            (TaskPriority::Low, Some("query-carol")),
+            (TaskPriority::High, None),
+        ];
+        let mut ids = Vec::new();
+        for (priority, assignee) in variants {
+            let mut req = new_task("Quarterly Report");
+            req.priority = priority;
+            req.assignees = assignee.map(|a| a.to_string()).into_iter().collect();
+            req.tags = vec!["query-finance".to_string()];
+            let created = expect_success(
+                create_task_http_rpc(TASK_MANAGER_URL, req),
+                "Failed to create task"
+            ).await?;
+            ids.push(created.task.map(|t| t.id).unwrap_or_default());
+        }
+        
+        let filter = || TaskFilter {
+            status: Some(TaskStatus::Pending),
+            priority: Some(TaskPriority::High),
+            assignee: Some("query-carol".to_string()),
+            tag: Some(" Query-Finance ".to_string()),
+            text: Some("quarterly".to_string()),
+        };
+        let http = expect_success(query_tasks_http_rpc(TASK_MANAGER_URL, filter()), "Failed to query tasks").await?;
+        let remote = expect_success(
+            query_tasks_remote_rpc(&task_manager_address(), filter()),
+            "Failed to query tasks remotely"
+        ).await?;
+        for results in [http, remote] {
+            let found: Vec<&String> = results.iter().map(|t| &t.id).collect();
+            if found != vec![&ids[0]] {
+                fail!(format!("Expected only {}, got {:?}", ids[0], found));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_duplicate_task: async {
+        let mut req = new_task("Original Task");
+        req.description = "Original description".to_string();
+        req.priority = TaskPriority::High;
+        req.tags = vec!["dup".to_string()];
+        let original = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, req),
+            "Failed to create task"
+        ).await?;
+        let Some(original) = original.task else {
+            fail!("Created task missing from response");
+        };
+        
+        let copy = expect_success(
+            duplicate_task_http_rpc(TASK_MANAGER_URL, original.id.clone()),
+            "Failed to duplicate task"
+        ).await?;
+        let Some(copy) = copy.task else {
+            fail!(format!("Duplicate failed: {}", copy.message));
+        };
+        if copy.id == original.id
+            || copy.title != "Copy of Original Task"
+            || copy.description != original.description
+            || copy.priority != original.priority
+            || copy.tags != original.tags
+            || copy.status != TaskStatus::Pending
+        {
+            fail!(format!("Unexpected copy: {:?}", copy));
+        }
+        
+        // Editing the copy leaves the original alone
+        expect_success(
+            update_task_http_rpc(TASK_MANAGER_URL, TaskUpdateRequest {
+                task_id: copy.id.clone(),
+                title: Some("Edited Copy".to_string()),
+                description: None,
+                assignees: None,
+                new_status: None,
+                parent_id: None,
+                expected_version: None,
+                correlation_id: None,
+            }),
+            "Failed to update copy"
+        ).await?;
+        let reread = expect_success(get_task_http_rpc(TASK_MANAGER_URL, original.id.clone()), "Failed to get task").await?;
+        if reread.task.map(|t| t.title) != Some(original.title.clone()) {
+            fail!("Editing the copy changed the original");
+        }
+        
+        let missing = expect_success(
+            duplicate_task_http_rpc(TASK_MANAGER_URL, "missing-task".to_string()),
+            "Failed to call duplicate_task"
+        ).await?;
+        if missing.success {
+            fail!("Duplicating an unknown task should fail");
+        }
+        
+        Ok(())
+    },
+
+    test_metadata_cap: async {
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Metadata Task")),
+            "Failed to create task"
+        ).await?;
+        let Some(task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        
+        // Fill the task up to the 32-key cap
+        for n in 0..32 {
+            let response = expect_success(
+                set_metadata_http_rpc(TASK_MANAGER_URL, task.id.clone(), format!("key-{}", n), n.to_string()),
+                "Failed to set metadata"
+            ).await?;
+            if !response.success {
+                fail!(format!("Setting key {} failed: {}", n, response.message));
+            }
+        }
+        
+        let over_cap = expect_success(
+            set_metadata_http_rpc(TASK_MANAGER_URL, task.id.clone(), "key-32".to_string(), "x".to_string()),
+            "Failed to call set_metadata"
+        ).await?;
+        if over_cap.success || over_cap.status_code != 400 {
+            fail!("A metadata key beyond the cap should be rejected");
+        }
+        
+        // Existing keys can still be overwritten at the cap
+        let overwrite = expect_success(
+            set_metadata_http_rpc(TASK_MANAGER_URL, task.id.clone(), "key-0".to_string(), "updated".to_string()),
+            "Failed to call set_metadata"
+        ).await?;
+        let Some(updated) = overwrite.task.filter(|_| overwrite.success) else {
+            fail!("Overwriting an existing key at the cap should succeed");
+        };
+        if !updated.metadata.contains(&("key-0".to_string(), "updated".to_string())) {
+            fail!("Overwritten metadata value missing");
+        }
+        
+        let too_long = expect_success(
+            set_metadata_http_rpc(TASK_MANAGER_URL, task.id.clone(), "key-1".to_string(), "x".repeat(1025)),
+            "Failed to call set_metadata"
+        ).await?;
+        if too_long.success {
+            fail!("An overlong metadata value should be rejected");
+        }
+        
+        let removed = expect_success(
+            remove_metadata_http_rpc(TASK_MANAGER_URL, task.id.clone(), "key-0".to_string()),
+            "Failed to remove metadata"
+        ).await?;
+        if removed.task.map(|t| t.metadata.len()) != Some(31) {
+            fail!("Removing a key should leave 31 keys");
+        }
+        
+        Ok(())
+    },
+
+    test_sorted_tasks: async {
+        let fixtures = [
+            ("b sorted", TaskPriority::High, Some(3_000_000_000), true),
+            ("A sorted", TaskPriority::Low, None, false),
+            ("c sorted", TaskPriority::Critical, Some(2_000_000_000), false),
+        ];
+        for (title, priority, due_at, start) in fixtures {
+            let mut req = new_task(title);
+            req.priority = priority;
+            req.due_at = due_at;
+            let created = expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?;
+            let Some(task) = created.task else {
+                fail!("Created task missing from response");
+            };
+            if start {
+                expect_success(
+                    update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                        task_id: task.id,
+                        new_status: TaskStatus::InProgress,
+                        expected_version: None,
+                        correlation_id: None,
+                    }),
+                    "Failed to start task"
+                ).await?;
+            }
+        }
+        
+        fn status_rank(status: &TaskStatus) -> u8 {
+            match status {
+                TaskStatus::Pending => 0,
+                TaskStatus::InProgress => 1,
+                TaskStatus::Completed => 2,
+                TaskStatus::Cancelled => 3,
+            }
+        }
+        fn priority_rank(priority: &TaskPriority) -> u8 {
+            match priority {
+                TaskPriority::Low => 0,
+                TaskPriority::Medium => 1,
+                TaskPriority::High => 2,
+                TaskPriority::Critical => 3,
+            }
+        }
+        
+        let fields = [
+            ("created_at", SortField::CreatedAt),
+            ("title", SortField::Title),
+            ("status", SortField::Status),
+            ("priority", SortField::Priority),
+            ("due_at", SortField::DueAt),
+            ("last_activity", SortField::LastActivity),
+            ("manual", SortField::Manual),
+        ];
+        for (name, field) in fields {
+            for descending in [false, true] {
+                let tasks = expect_success(
+                    get_all_tasks_sorted_http_rpc(TASK_MANAGER_URL, SortSpec { field: field.clone(), descending }),
+                    "Failed to get sorted tasks"
+                ).await?;
+                for pair in tasks.windows(2) {
+                    let (a, b) = (&pair[0], &pair[1]);
+                    let ordering = match field {
+                        SortField::CreatedAt => a.created_at.cmp(&b.created_at),
+                        SortField::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+                        SortField::Status => status_rank(&a.status).cmp(&status_rank(&b.status)),
+                        SortField::Priority => priority_rank(&a.priority).cmp(&priority_rank(&b.priority)),
+                        // Tasks without a due date come last when ascending
+                        SortField::DueAt => a.due_at.unwrap_or(u64::MAX).cmp(&b.due_at.unwrap_or(u64::MAX)),
+                        SortField::LastActivity => a.last_activity_at.cmp(&b.last_activity_at),
+                        SortField::Manual => a.sort_order.total_cmp(&b.sort_order),
+                    };
+                    let ordering = if descending { ordering.reverse() } else { ordering };
+                    if ordering.then_with(|| a.id.cmp(&b.id)) != std::cmp::Ordering::Less {
+                        fail!(format!(
+                            "Sorting by {} ({}) put {} before {}",
+                            name,
+                            if descending { "descending" } else { "ascending" },
+                            a.title,
+                            b.title
+                        ));
+                    }
+                }
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_create_task_process_rpc: async {
+        let address = task_manager_address();
+        
+        let local = expect_success(
+            create_task_local_rpc(&address, new_task("Local RPC Task")),
+            "Failed to create task locally"
+        ).await?;
+        let remote = expect_success(
+            create_task_remote_rpc(&address, new_task("Remote RPC Task")),
+            "Failed to create task remotely"
+        ).await?;
+        
+        // Both tasks should be readable back over HTTP
+        for (response, title) in [(local, "Local RPC Task"), (remote, "Remote RPC Task")] {
+            let Some(task) = response.task.filter(|_| response.success) else {
+                fail!(format!("Creating {:?} failed: {}", title, response.message));
+            };
+            let fetched = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to get task").await?;
+            if fetched.task.map(|t| t.title) != Some(title.to_string()) {
+                fail!(format!("Task {:?} missing after creation", title));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_get_tasks_by_ids: async {
+        let mut ids = Vec::new();
+        for title in ["By Id One", "By Id Two"] {
+            let created = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task(title)), "Failed to create task").await?;
+            let Some(task) = created.task else {
+                fail!("Created task missing from response");
+            };
+            ids.push(task.id);
+        }
+        
+        // Ask for the second task first, with unknown ids mixed in
+        let request = vec![
+            ids[1].clone(),
+            "missing-one".to_string(),
+            ids[0].clone(),
+            "missing-two".to_string(),
+        ];
+        let response = expect_success(get_tasks_by_ids_http_rpc(TASK_MANAGER_URL, request), "Failed to get tasks by ids").await?;
+        let found: Vec<String> = response.found.into_iter().map(|task| task.id).collect();
+        if found != vec![ids[1].clone(), ids[0].clone()] {
+            fail!(format!("Found tasks out of request order: {:?}", found));
+        }
+        if response.missing != vec!["missing-one".to_string(), "missing-two".to_string()] {
+            fail!(format!("Unexpected missing ids: {:?}", response.missing));
+        }
+        
+        Ok(())
+    },
+
+    test_multiple_assignees: async {
+        let mut req = new_task("Shared Task");
+        req.assignees = vec!["multi-alice".to_string()];
+        let created = expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?;
+        let Some(task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        
+        // Adding twice is a no-op the second time
+        for _ in 0..2 {
+            expect_success(
+                add_assignee_http_rpc(TASK_MANAGER_URL, task.id.clone(), "multi-bob".to_string()),
+                "Failed to add assignee"
+            ).await?;
+        }
+        let bobs = expect_success(
+            get_tasks_by_assignee_local_rpc(&task_manager_address(), "multi-bob".to_string()),
+            "Failed to get tasks by assignee"
+        ).await?;
+        let Some(shared) = bobs.into_iter().find(|t| t.id == task.id) else {
+            fail!("Task should be found by its second assignee");
+        };
+        if shared.assignees != vec!["multi-alice".to_string(), "multi-bob".to_string()] {
+            fail!(format!("Unexpected assignees: {:?}", shared.assignees));
+        }
+        
+        for _ in 0..2 {
+            expect_success(
+                remove_assignee_http_rpc(TASK_MANAGER_URL, task.id.clone(), "multi-alice".to_string()),
+                "Failed to remove assignee"
+            ).await?;
+        }
+        let reread = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to get task").await?;
+        let Some(reread) = reread.task else {
+            fail!("Task missing after removing an assignee");
+        };
+        if reread.assignees != vec!["multi-bob".to_string()] {
+            fail!(format!("Unexpected assignees after removal: {:?}", reread.assignees));
+        }
+        // One add and one removal; the repeated calls recorded nothing
+        let reassignments = reread
+            .history
+            .iter()
+            .filter(|event| matches!(event.kind, TaskEventKind::Reassigned(_)))
+            .count();
+        if reassignments != 2 {
+            fail!(format!("Expected 2 reassignment events, got {}", reassignments));
+        }
+        
+        Ok(())
+    },
+
+    test_single_assignee_migration: async {
+        // Tasks in the pre-multi-assignee format, as older state and storage hold them
+        let old_task = |id: &str, assigned_to: serde_json::Value| serde_json::json!({
+            "id": id,
+            "title": "Legacy Task",
+            "description": "",
+            "status": "Pending",
+            "created_at": 1,
+            "assigned_to": assigned_to,
+            "history": [{
+                "at": 1,
+                "kind": { "Reassigned": { "from": null, "to": assigned_to } },
+            }],
+        });
+        let request = serde_json::json!({
+            "ImportTasks": (
+                vec![
+                    old_task("legacy-assigned-task", serde_json::json!("legacy-alice")),
+                    old_task("legacy-unassigned-task", serde_json::Value::Null),
+                ],
+                "RegenerateId",
+            )
+        });
+        let imported = expect_success(
+            caller_utils::send_http_rpc::<caller_utils::BulkCreateResponse>(&request, TASK_MANAGER_URL),
+            "Failed to import old-format tasks"
+        ).await?;
+        if !imported.failed.is_empty() || imported.created.len() != 2 {
+            fail!(format!("Old-format tasks should import cleanly, failed: {:?}", imported.failed));
+        }
+        
+        let assignees: Vec<Vec<String>> = imported.created.iter().map(|t| t.assignees.clone()).collect();
+        if assignees != vec![vec!["legacy-alice".to_string()], Vec::new()] {
+            fail!(format!("Old assigned_to should become the assignee list, got {:?}", assignees));
+        }
+        let TaskEventKind::Reassigned(event) = &imported.created[0].history[0].kind else {
+            fail!("Old reassignment history should be kept");
+        };
+        if !event.from.is_empty() || event.to != vec!["legacy-alice".to_string()] {
+            fail!(format!("Old reassignment event migrated wrongly: {:?}", event));
+        }
+        
+        Ok(())
+    },
+
+    test_last_activity: async {
+        // Import a long-idle task stored before last_activity_at existed
+        let request = serde_json::json!({
+            "ImportTasks": (
+                vec![serde_json::json!({
+                    "id": "idle-task",
+                    "title": "Idle Task",
+                    "description": "",
+                    "status": "Pending",
+                    "created_at": 1,
+                })],
+                "RegenerateId",
+            )
+        });
+        let imported = expect_success(
+            caller_utils::send_http_rpc::<caller_utils::BulkCreateResponse>(&request, TASK_MANAGER_URL),
+            "Failed to import task"
+        ).await?;
+        let Some(task) = imported.created.into_iter().next() else {
+            fail!(format!("Import failed: {:?}", imported.failed));
+        };
+        if task.last_activity_at != task.created_at {
+            fail!(format!("Missing last_activity_at should default to created_at, got {}", task.last_activity_at));
+        }
+        
+        let commented = expect_success(
+            add_comment_http_rpc(TASK_MANAGER_URL, task.id.clone(), "tester".to_string(), "Still on it".to_string()),
+            "Failed to add comment"
+        ).await?;
+        let Some(commented) = commented.task else {
+            fail!("Commented task missing from response");
+        };
+        if commented.last_activity_at <= commented.created_at {
+            fail!("A comment should bump last_activity_at above created_at");
+        }
+        
+        // Touching updates the timestamp without a version bump
+        let touched = expect_success(touch_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to touch task").await?;
+        let Some(touched) = touched.task else {
+            fail!("Touched task missing from response");
+        };
+        if touched.version != commented.version || touched.last_activity_at < commented.last_activity_at {
+            fail!("Touching should only move last_activity_at forward");
+        }
+        
+        Ok(())
+    },
+
+    test_external_task_change: async {
+        let address = task_manager_address();
+        let created = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Externally Edited")),
+            "Failed to create task"
+        ).await?;
+        let Some(mut task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        
+        // Storage reports a newer version of the task
+        task.title = "Edited in Storage".to_string();
+        task.version += 1;
+        test_remote_call(
+            on_external_task_change_remote_rpc(&address, task.clone()),
+            true,
+            "Newer external version should be applied"
+        ).await?;
+        let reread = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to get task").await?;
+        if reread.task.map(|t| t.title) != Some("Edited in Storage".to_string()) {
+            fail!("External change should update the local task");
+        }
+        
+        // Replaying the same version is ignored
+        task.title = "Stale Edit".to_string();
+        test_remote_call(
+            on_external_task_change_remote_rpc(&address, task.clone()),
+            false,
+            "Stale external version should be ignored"
+        ).await?;
+        
+        // Tasks created elsewhere are added
+        task.id = "external-only-task".to_string();
+        test_remote_call(
+            on_external_task_change_remote_rpc(&address, task.clone()),
+            true,
+            "Unknown external task should be added"
+        ).await?;
+        let added = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to get task").await?;
+        if !added.success {
+            fail!("Externally created task should be readable");
+        }
+        
+        Ok(())
+    },
+
+    test_batch_limits: async {
+        let before = expect_success(get_counts_http_rpc(TASK_MANAGER_URL), "Failed to get counts").await?;
+        
+        // Too many tasks
+        let reqs: Vec<NewTaskRequest> = (0..1001).map(|n| new_task(&format!("Batch {}", n))).collect();
+        let too_many = expect_success(create_tasks_bulk_http_rpc(TASK_MANAGER_URL, reqs), "Failed to call create_tasks_bulk").await?;
+        if !too_many.created.is_empty() || too_many.failed.len() != 1 {
+            fail!(format!("A 1001-task batch should be rejected whole, got {:?}", too_many.failed));
+        }
+        
+        // Few tasks, but too much text between them
+        let reqs: Vec<NewTaskRequest> = (0..150)
+            .map(|n| {
+                let mut req = new_task(&format!("Wordy {}", n));
+                req.description = "x".repeat(8000);
+                req
+            })
+            .collect();
+        let too_big = expect_success(create_tasks_bulk_http_rpc(TASK_MANAGER_URL, reqs), "Failed to call create_tasks_bulk").await?;
+        if !too_big.created.is_empty() || too_big.failed.len() != 1 {
+            fail!(format!("An oversized batch should be rejected whole, got {:?}", too_big.failed));
+        }
+        
+        // Imports have the same limit
+        let Some(template) = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Import Template")),
+            "Failed to create task"
+        ).await?.task else {
+            fail!("Created task missing from response");
+        };
+        let tasks = vec![template; 1001];
+        let import = expect_success(
+            import_tasks_http_rpc(TASK_MANAGER_URL, tasks, ImportConflict::RegenerateId),
+            "Failed to call import_tasks"
+        ).await?;
+        if !import.created.is_empty() || import.failed.len() != 1 {
+            fail!("A 1001-task import should be rejected whole");
+        }
+        
+        // Nothing but the template was created
+        let after = expect_success(get_counts_http_rpc(TASK_MANAGER_URL), "Failed to get counts").await?;
+        if after.total != before.total + 1 {
+            fail!(format!("Rejected batches changed the task count: {} -> {}", before.total, after.total));
+        }
+        
+        Ok(())
+    },
+
+    test_tag_cloud: async {
+        // cloud-a on three tasks, cloud-b and cloud-c on two each
+        let tag_sets = [
+            vec!["cloud-a", "cloud-c"],
+            vec!["cloud-a", "cloud-b"],
+            vec!["cloud-a", "cloud-b", "cloud-c"],
+        ];
+        for tags in tag_sets {
+            let mut req = new_task("Cloud Task");
+            req.tags = tags.into_iter().map(|tag| tag.to_string()).collect();
+            expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?;
+        }
+        
+        let cloud = expect_success(get_tag_cloud_http_rpc(TASK_MANAGER_URL), "Failed to get tag cloud").await?;
+        let ours: Vec<(String, u64)> = cloud
+            .iter()
+            .filter(|entry| entry.tag.starts_with("cloud-"))
+            .map(|entry| (entry.tag.clone(), entry.count))
+            .collect();
+        let expected = vec![
+            ("cloud-a".to_string(), 3),
+            ("cloud-b".to_string(), 2),
+            ("cloud-c".to_string(), 2),
+        ];
+        if ours != expected {
+            fail!(format!("Unexpected tag cloud: {:?}", ours));
+        }
+        if cloud.windows(2).any(|pair| (pair[1].count, &pair[0].tag) > (pair[0].count, &pair[1].tag)) {
+            fail!("Tag cloud should be sorted by count descending, then tag");
+        }
+        
+        Ok(())
+    },
+
+    test_completion_metrics: async {
+        let address = task_manager_address();
+        
+        // Two tasks completed now
+        let mut template = None;
+        for title in ["Velocity One", "Velocity Two"] {
+            let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task(title)), "Failed to create task").await?.task else {
+                fail!("Created task missing from response");
+            };
+            for status in [TaskStatus::InProgress, TaskStatus::Completed] {
+                expect_success(
+                    update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                        task_id: task.id.clone(),
+                        new_status: status,
+                        expected_version: None,
+                        correlation_id: None,
+                    }),
+                    "Failed to update status"
+                ).await?;
+            }
+            template = Some(task);
+        }
+        let Some(mut old) = template else {
+            fail!("No task created");
+        };
+        let since = old.created_at;
+        let recent = expect_success(get_completion_metrics_local_rpc(&address, since), "Failed to get metrics").await?;
+        if recent.completed_in_window < 2 || recent.avg_time_to_complete_secs.is_none() {
+            fail!(format!("Both completions should be in the window: {:?}", recent));
+        }
+        
+        // One task created at t=50 and completed at t=100, long before the others
+        old.status = TaskStatus::Completed;
+        old.created_at = 50;
+        old.completed_at = Some(100);
+        old.history = Vec::new();
+        let imported = expect_success(
+            import_tasks_http_rpc(TASK_MANAGER_URL, vec![old], ImportConflict::RegenerateId),
+            "Failed to import old task"
+        ).await?;
+        if imported.created.len() != 1 {
+            fail!(format!("Old task should import, failed: {:?}", imported.failed));
+        }
+        
+        // A window starting at t=60 catches the old completion but not its creation
+        let wide = expect_success(get_completion_metrics_local_rpc(&address, 60), "Failed to get metrics").await?;
+        let narrow = expect_success(get_completion_metrics_local_rpc(&address, 101), "Failed to get metrics").await?;
+        if wide.completed_in_window != narrow.completed_in_window + 1 || wide.created_in_window != narrow.created_in_window {
+            fail!(format!("Old completion should fall between the windows: {:?} vs {:?}", wide, narrow));
+        }
+        
+        // An empty window doesn't divide by zero
+        let empty = expect_success(get_completion_metrics_local_rpc(&address, u64::MAX), "Failed to get metrics").await?;
+        if empty.completed_in_window != 0 || empty.completion_rate != 0.0 || empty.avg_time_to_complete_secs.is_some() {
+            fail!(format!("Empty window should report no completions: {:?}", empty));
+        }
+        
+        Ok(())
+    },
+
+    test_ws_token: async {
+        let address = task_manager_address();
+        
+        test_remote_call(
+            set_ws_token_local_rpc(&address, Some("s3cret-token".to_string())),
+            true,
+            "A non-empty token should be accepted"
+        ).await?;
+        test_remote_call(
+            set_ws_token_local_rpc(&address, Some(String::new())),
+            false,
+            "An empty token should be rejected"
+        ).await?;
+        
+        // Leave subscriptions open for everything else
+        test_remote_call(
+            set_ws_token_local_rpc(&address, None),
+            true,
+            "Clearing the token should be accepted"
+        ).await?;
+        
+        Ok(())
+    },
+
+    test_manual_order: async {
+        let mut ids = Vec::new();
+        for title in ["Order A", "Order B", "Order C"] {
+            let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task(title)), "Failed to create task").await?.task else {
+                fail!("Created task missing from response");
+            };
+            ids.push(task.id);
+        }
+        let (a, b, c) = (ids[0].clone(), ids[1].clone(), ids[2].clone());
+        
+        // The manual order of just these three tasks, by title
+        async fn order_of(ids: &[String]) -> anyhow::Result<Vec<String>> {
+            let tasks = expect_success(
+                get_all_tasks_sorted_http_rpc(TASK_MANAGER_URL, SortSpec { field: SortField::Manual, descending: false }),
+                "Failed to get tasks in manual order"
+            ).await?;
+            Ok(tasks.into_iter().filter(|task| ids.contains(&task.id)).map(|task| task.title).collect())
+        }
+        
+        expect_success(move_task_to_top_http_rpc(TASK_MANAGER_URL, c.clone()), "Failed to move task to top").await?;
+        expect_success(move_task_to_bottom_http_rpc(TASK_MANAGER_URL, a.clone()), "Failed to move task to bottom").await?;
+        let order = order_of(&ids).await?;
+        if order != ["Order C", "Order B", "Order A"] {
+            fail!(format!("Unexpected order after moving to top and bottom: {:?}", order));
+        }
+        
+        // Repeatedly moving between the same neighbours halves the gap until it renumbers
+        for _ in 0..80 {
+            expect_success(move_task_before_http_rpc(TASK_MANAGER_URL, a.clone(), b.clone()), "Failed to move A before B").await?;
+            expect_success(move_task_before_http_rpc(TASK_MANAGER_URL, b.clone(), a.clone()), "Failed to move B before A").await?;
+        }
+        let order = order_of(&ids).await?;
+        if order != ["Order C", "Order B", "Order A"] {
+            fail!(format!("Unexpected order after repeated moves: {:?}", order));
+        }
+        expect_success(move_task_before_http_rpc(TASK_MANAGER_URL, a.clone(), c.clone()), "Failed to move A before C").await?;
+        let order = order_of(&ids).await?;
+        if order != ["Order A", "Order C", "Order B"] {
+            fail!(format!("Unexpected order after moving A before C: {:?}", order));
+        }
+        
+        let response = expect_success(
+            move_task_before_http_rpc(TASK_MANAGER_URL, a.clone(), a.clone()),
+            "Failed to call move_task_before"
+        ).await?;
+        if response.success {
+            fail!("Moving a task before itself should fail");
+        }
+        
+        Ok(())
+    },
+
+    test_cancel_task: async {
+        let Some(pending) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Cancel Me")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        let response = expect_success(
+            cancel_task_http_rpc(TASK_MANAGER_URL, pending.id.clone(), Some("No longer needed".to_string())),
+            "Failed to cancel task"
+        ).await?;
+        let Some(cancelled) = response.task.filter(|_| response.success) else {
+            fail!(format!("Cancelling a pending task should succeed: {}", response.message));
+        };
+        if cancelled.status != TaskStatus::Cancelled {
+            fail!(format!("Task should be Cancelled, got {:?}", cancelled.status));
+        }
+        let recorded = cancelled.history.iter().any(|event| matches!(
+            &event.kind,
+            TaskEventKind::Cancelled(cancelled) if cancelled.reason.as_deref() == Some("No longer needed")
+        ));
+        if !recorded {
+            fail!(format!("History should record the cancellation reason: {:?}", cancelled.history));
+        }
+        
+        let Some(done) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Already Done")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        for status in [TaskStatus::InProgress, TaskStatus::Completed] {
+            expect_success(
+                update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                    task_id: done.id.clone(),
+                    new_status: status,
+                    expected_version: None,
+                    correlation_id: None,
+                }),
+                "Failed to update status"
+            ).await?;
+        }
+        let response = expect_success(cancel_task_http_rpc(TASK_MANAGER_URL, done.id.clone(), None), "Failed to call cancel_task").await?;
+        if response.success || !response.message.contains("Completed") {
+            fail!(format!("Cancelling a completed task should fail naming its status: {}", response.message));
+        }
+        if response.task.map(|task| task.status) != Some(TaskStatus::Completed) {
+            fail!("A rejected cancellation should leave the task Completed");
+        }
+        
+        Ok(())
+    },
+
+    test_correlation_id: async {
+        let address = task_manager_address();
+        let storage = expect_success(get_storage_address_local_rpc(&address), "Failed to get storage address").await?;
+        
+        // A missing storage process stands in for storage; the id must still reach the storage call
+        expect_success(set_storage_attempts_local_rpc(&address, 1), "Failed to set storage attempts").await?;
+        expect_success(
+            set_storage_address_local_rpc(&address, "indexer.os@missing-storage:app:sys".to_string()),
+            "Failed to set storage address"
+        ).await?;
+        let mut req = new_task("Traced Task");
+        req.correlation_id = Some("trace-create-1".to_string());
+        let created = expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?;
+        let stored_with = expect_success(
+            get_last_storage_correlation_id_local_rpc(&address),
+            "Failed to get storage correlation id"
+        ).await?;
+        expect_success(set_storage_address_local_rpc(&address, storage), "Failed to restore storage address").await?;
+        expect_success(set_storage_attempts_local_rpc(&address, 3), "Failed to restore storage attempts").await?;
+        
+        if created.correlation_id.as_deref() != Some("trace-create-1") {
+            fail!(format!("Supplied correlation id should be echoed, got {:?}", created.correlation_id));
+        }
+        if stored_with.as_deref() != Some("trace-create-1") {
+            fail!(format!("Supplied correlation id should reach storage, got {:?}", stored_with));
+        }
+        
+        // Without one, the server generates an id and uses it for the storage call too
+        let Some(task) = created.task else {
+            fail!("Created task missing from response");
+        };
+        let updated = expect_success(
+            update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+                task_id: task.id.clone(),
+                new_status: TaskStatus::InProgress,
+                expected_version: None,
+                correlation_id: None,
+            }),
+            "Failed to update status"
+        ).await?;
+        let stored_with = expect_success(
+            get_last_storage_correlation_id_local_rpc(&address),
+            "Failed to get storage correlation id"
+        ).await?;
+        if updated.correlation_id.as_ref().is_none_or(|id| id.is_empty()) || updated.correlation_id != stored_with {
+            fail!(format!("Generated id {:?} should match storage's {:?}", updated.correlation_id, stored_with));
+        }
+        
+        Ok(())
+    },
+
+    test_task_summaries: async {
+        let mut req = new_task("Summarized Task");
+        req.description = "A description summaries leave out".to_string();
+        expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?;
+        
+        // Check the raw JSON, since the typed summary couldn't carry extra fields anyway
+        let summaries = expect_success(
+            caller_utils::send_http_rpc::<Vec<serde_json::Value>>(&serde_json::json!({"ListTaskSummaries": {}}), TASK_MANAGER_URL),
+            "Failed to list task summaries"
+        ).await?;
+        for summary in &summaries {
+            let keys: Vec<&String> = summary.as_object().map(|fields| fields.keys().collect()).unwrap_or_default();
+            if keys.len() != 3 || ["id", "title", "status"].iter().any(|key| summary.get(key).is_none()) {
+                fail!(format!("Summary should only carry id, title and status: {}", summary));
+            }
+        }
+        if !summaries.iter().any(|summary| summary["title"] == "Summarized Task") {
+            fail!("New task missing from summaries");
+        }
+        
+        let stats = expect_success(get_statistics_local_rpc(&task_manager_address()), "Failed to get statistics").await?;
+        if summaries.len() as u64 != stats.total_tasks {
+            fail!(format!("Expected {} summaries, got {}", stats.total_tasks, summaries.len()));
+        }
+        
+        Ok(())
+    },
+
+    test_task_templates: async {
+        let template = TaskTemplate {
+            title: "Weekly Report".to_string(),
+            description: "Summarize the week".to_string(),
+            priority: TaskPriority::High,
+            tags: vec!["reports".to_string()],
+        };
+        test_remote_call(
+            save_template_http_rpc(TASK_MANAGER_URL, "weekly".to_string(), template),
+            true,
+            "Failed to save template"
+        ).await?;
+        let templates = expect_success(list_templates_http_rpc(TASK_MANAGER_URL), "Failed to list templates").await?;
+        if !templates.iter().any(|(name, _)| name == "weekly") {
+            fail!("Saved template missing from list");
+        }
+        
+        // No overrides: everything comes from the template
+        let response = expect_success(
+            create_from_template_http_rpc(TASK_MANAGER_URL, "weekly".to_string(), new_task(""), None),
+            "Failed to create from template"
+        ).await?;
+        let Some(task) = response.task.filter(|_| response.success) else {
+            fail!(format!("Creating from template should succeed: {}", response.message));
+        };
+        if task.title != "Weekly Report" || task.description != "Summarize the week"
+            || task.priority != TaskPriority::High || task.tags != vec!["reports".to_string()]
+        {
+            fail!(format!("Task should match the template: {:?}", task));
+        }
+        
+        // Overrides win over the template; unset fields still come from it
+        let mut overrides = new_task("Quarterly Report");
+        overrides.assignees = vec!["alice".to_string()];
+        let response = expect_success(
+            create_from_template_http_rpc(TASK_MANAGER_URL, "weekly".to_string(), overrides, Some(TaskPriority::Critical)),
+            "Failed to create from template"
+        ).await?;
+        let Some(task) = response.task.filter(|_| response.success) else {
+            fail!(format!("Creating from template should succeed: {}", response.message));
+        };
+        if task.title != "Quarterly Report" || task.priority != TaskPriority::Critical
+            || task.description != "Summarize the week" || task.assignees != vec!["alice".to_string()]
+        {
+            fail!(format!("Overrides should replace template fields: {:?}", task));
+        }
+        
+        // Medium is the default priority, but overriding to it still wins over the template
+        let response = expect_success(
+            create_from_template_http_rpc(TASK_MANAGER_URL, "weekly".to_string(), new_task(""), Some(TaskPriority::Medium)),
+            "Failed to create from template"
+        ).await?;
+        let Some(task) = response.task.filter(|_| response.success) else {
+            fail!(format!("Creating from template should succeed: {}", response.message));
+        };
+        if task.priority != TaskPriority::Medium || task.title != "Weekly Report" {
+            fail!(format!("An explicit Medium priority should replace the template's: {:?}", task));
+        }
+        
+        test_remote_call(delete_template_http_rpc(TASK_MANAGER_URL, "weekly".to_string()), true, "Failed to delete template").await?;
+        let response = expect_success(
+            create_from_template_http_rpc(TASK_MANAGER_URL, "weekly".to_string(), new_task(""), None),
+            "Failed to call create_from_template"
+        ).await?;
+        if response.success || response.status_code != 400 {
+            fail!("Creating from a deleted template should fail");
+        }
+        
+        Ok(())
+    },
+
+    test_ws_hello: async {
+        let sequence = expect_success(
+            get_subscribe_sequence_local_rpc(&task_manager_address()),
+            "Failed to get subscribe sequence"
+        ).await?;
+        if sequence.first().map(String::as_str) != Some("Hello") {
+            fail!(format!("Subscribers should be greeted with Hello first: {:?}", sequence));
+        }
+        if sequence.last().map(String::as_str) != Some("SnapshotComplete") {
+            fail!(format!("The snapshot should follow Hello: {:?}", sequence));
+        }
+        
+        Ok(())
+    },
+
+    test_tasks_if_changed: async {
+        // Epoch 0 predates the welcome task, so the first poll always gets the tasks
+        let TasksSince::Changed(first) = expect_success(
+            get_all_tasks_if_changed_http_rpc(TASK_MANAGER_URL, 0),
+            "Failed to poll tasks"
+        ).await? else {
+            fail!("Polling from epoch 0 should return the tasks");
+        };
+        
+        let unchanged = expect_success(
+            get_all_tasks_if_changed_http_rpc(TASK_MANAGER_URL, first.epoch),
+            "Failed to poll tasks"
+        ).await?;
+        if !matches!(&unchanged, TasksSince::Unchanged(u) if u.epoch == first.epoch) {
+            fail!(format!("Polling the current epoch should report Unchanged: {:?}", unchanged));
+        }
+        
+        expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Epoch Bump")), "Failed to create task").await?;
+        let TasksSince::Changed(after) = expect_success(
+            get_all_tasks_if_changed_http_rpc(TASK_MANAGER_URL, first.epoch),
+            "Failed to poll tasks"
+        ).await? else {
+            fail!("A mutation should change the epoch");
+        };
+        if after.epoch <= first.epoch || !after.tasks.iter().any(|t| t.title == "Epoch Bump") {
+            fail!(format!("Epoch should increase and include the new task: {} -> {}", first.epoch, after.epoch));
+        }
+        
+        Ok(())
+    },
+
+    test_tasks_changed_since: async {
+        // Imported tasks keep their activity times, so the cutoff can fall between them
+        let idle_task = |id: &str, last_activity_at: u64| serde_json::json!({
+            "id": id,
+            "title": "Delta Task",
+            "description": "",
+            "status": "Pending",
+            "created_at": 1,
+            "last_activity_at": last_activity_at,
+        });
+        let request = serde_json::json!({
+            "ImportTasks": (
+                vec![idle_task("delta-before-cutoff", 100), idle_task("delta-after-cutoff", 200)],
+                "RegenerateId",
+            )
+        });
+        let imported = expect_success(
+            caller_utils::send_http_rpc::<caller_utils::BulkCreateResponse>(&request, TASK_MANAGER_URL),
+            "Failed to import tasks"
+        ).await?;
+        let [before, after] = &imported.created[..] else {
+            fail!(format!("Both tasks should import, failed: {:?}", imported.failed));
+        };
+        
+        let changed = expect_success(get_tasks_changed_since_http_rpc(TASK_MANAGER_URL, 150), "Failed to get changed tasks").await?;
+        if changed.iter().any(|t| t.id == before.id) || !changed.iter().any(|t| t.id == after.id) {
+            fail!("Only tasks active after the cutoff should be returned");
+        }
+        if changed.windows(2).any(|pair| pair[0].last_activity_at > pair[1].last_activity_at) {
+            fail!("Changed tasks should be ordered by last activity, oldest first");
+        }
+        
+        Ok(())
+    },
+
+    test_tombstones: async {
+        let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Doomed Task")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        expect_success(delete_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to delete task").await?;
+        
+        // The deletion can't predate the creation, so a cutoff just before it catches the tombstone
+        let cutoff = task.created_at.saturating_sub(1);
+        let tombstones = expect_success(get_tombstones_since_http_rpc(TASK_MANAGER_URL, cutoff), "Failed to get tombstones").await?;
+        if !tombstones.iter().any(|t| t.id == task.id && t.deleted_at > cutoff) {
+            fail!(format!("Deleted task {} should have a tombstone: {:?}", task.id, tombstones));
+        }
+        
+        let later = expect_success(get_tombstones_since_http_rpc(TASK_MANAGER_URL, u64::MAX), "Failed to get tombstones").await?;
+        if !later.is_empty() {
+            fail!("No tombstones should be newer than the end of time");
+        }
+        
+        Ok(())
+    },
+
+    test_sync_all_to_storage: async {
+        let address = task_manager_address();
+        let storage = expect_success(get_storage_address_local_rpc(&address), "Failed to get storage address").await?;
+        let before = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        
+        // A missing storage process fails every write; the report lists them all and local
+        // tasks stay as they were
+        expect_success(set_storage_attempts_local_rpc(&address, 1), "Failed to set storage attempts").await?;
+        expect_success(
+            set_storage_address_local_rpc(&address, "indexer.os@missing-storage:app:sys".to_string()),
+            "Failed to set storage address"
+        ).await?;
+        let report = expect_success(sync_all_to_storage_local_rpc(&address), "Failed to sync to storage").await?;
+        expect_success(set_storage_address_local_rpc(&address, storage), "Failed to restore storage address").await?;
+        expect_success(set_storage_attempts_local_rpc(&address, 3), "Failed to restore storage attempts").await?;
+        if report.attempted != before.total_tasks || report.succeeded != 0 || report.failed.len() as u64 != report.attempted {
+            fail!(format!("Every task should fail against missing storage: {:?}", report));
+        }
+        let after = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        if after.total_tasks != before.total_tasks {
+            fail!("A failed sync must not change local tasks");
+        }
+        
+        // Against real storage a repeated sync succeeds and drains the queue
+        let health = expect_success(health_http_rpc(TASK_MANAGER_URL), "Failed to get health").await?;
+        if health.storage_reachable {
+            for _ in 0..2 {
+                let report = expect_success(sync_all_to_storage_local_rpc(&address), "Failed to sync to storage").await?;
+                if report.succeeded != report.attempted || !report.failed.is_empty() {
+                    fail!(format!("Sync to reachable storage should succeed: {:?}", report));
+                }
+            }
+            let pending = expect_success(sync_pending_storage_local_rpc(&address), "Failed to sync storage").await?;
+            if !pending.is_empty() {
+                fail!(format!("Nothing should stay queued after a full sync: {:?}", pending));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_max_tasks_eviction: async {
+        let address = task_manager_address();
+        
+        // Make sure at least one finished task can be evicted
+        let Some(finished) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Evictable Task")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        expect_success(cancel_task_http_rpc(TASK_MANAGER_URL, finished.id.clone(), None), "Failed to cancel task").await?;
+        
+        let before = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        test_remote_call(set_max_tasks_local_rpc(&address, Some(before.total_tasks)), true, "Failed to set task cap").await?;
+        let response = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Over The Cap")), "Failed to create task").await?;
+        let after = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        test_remote_call(set_max_tasks_local_rpc(&address, None), true, "Failed to clear task cap").await?;
+        if !response.success {
+            fail!(format!("Create at the cap should evict a finished task: {}", response.message));
+        }
+        if after.total_tasks != before.total_tasks || after.pending_tasks != before.pending_tasks + 1 {
+            fail!(format!("One finished task should make way for the new one: {:?} -> {:?}", before, after));
+        }
+        
+        Ok(())
+    },
+
+    test_max_tasks_rejection: async {
+        let address = task_manager_address();
+        
+        // A cap of one can't be met by evicting finished tasks while any open task exists
+        let Some(exported) = expect_success(get_all_tasks_http_rpc(TASK_MANAGER_URL), "Failed to get tasks").await?.into_iter().next() else {
+            fail!("Expected at least one task to import");
+        };
+        let before = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        test_remote_call(set_max_tasks_local_rpc(&address, Some(1)), true, "Failed to set task cap").await?;
+        let response = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Rejected By Cap")), "Failed to call create_task").await?;
+        let imported = expect_success(
+            import_tasks_http_rpc(TASK_MANAGER_URL, vec![exported], ImportConflict::RegenerateId),
+            "Failed to call import_tasks"
+        ).await?;
+        let after = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        test_remote_call(set_max_tasks_local_rpc(&address, None), true, "Failed to clear task cap").await?;
+        if response.success || response.status_code != 409 || !response.message.contains("limit") {
+            fail!(format!("Create beyond the cap should be rejected with a clear message: {}", response.message));
+        }
+        if !imported.created.is_empty() || !imported.failed.iter().any(|reason| reason.contains("limit")) {
+            fail!(format!("Import beyond the cap should be rejected with a clear message: {:?}", imported.failed));
+        }
+        if after.total_tasks != before.total_tasks {
+            fail!("A rejected create or import must not evict anything");
+        }
+        
+        test_remote_call(set_max_tasks_local_rpc(&address, Some(0)), false, "A cap of zero should be rejected").await?;
+        
+        Ok(())
+    },
+
+    test_task_errors: async {
+        let address = task_manager_address();
+        
+        let response = expect_success(get_task_http_rpc(TASK_MANAGER_URL, "no-such-task".to_string()), "Failed to call get_task").await?;
+        if response.error != Some(TaskError::NotFound) {
+            fail!(format!("Unknown id should report NotFound: {:?}", response.error));
+        }
+        
+        let response = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("  ")), "Failed to call create_task").await?;
+        if !matches!(response.error, Some(TaskError::Validation(_))) {
+            fail!(format!("Blank title should report Validation: {:?}", response.error));
+        }
+        
+        let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Error Variants")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        if let Some(error) = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to get task").await?.error {
+            fail!(format!("A successful call should carry no error: {:?}", error));
+        }
+        
+        let stale = TaskStatusUpdateRequest {
+            task_id: task.id.clone(),
+            new_status: TaskStatus::InProgress,
+            expected_version: Some(task.version + 1),
+            correlation_id: None,
+        };
+        let response = expect_success(update_task_status_http_rpc(TASK_MANAGER_URL, stale), "Failed to call update_task_status").await?;
+        if response.error != Some(TaskError::VersionConflict) {
+            fail!(format!("Stale version should report VersionConflict: {:?}", response.error));
+        }
+        
+        let illegal = TaskStatusUpdateRequest {
+            task_id: task.id.clone(),
+            new_status: TaskStatus::Completed,
+            expected_version: None,
+            correlation_id: None,
+        };
+        let response = expect_success(update_task_status_http_rpc(TASK_MANAGER_URL, illegal), "Failed to call update_task_status").await?;
+        match response.error {
+            Some(TaskError::IllegalTransition(t)) if t.from == TaskStatus::Pending && t.to == TaskStatus::Completed => {}
+            other => {
+                fail!(format!("Pending -> Completed should report IllegalTransition: {:?}", other));
+            }
+        }
+        
+        // The create still succeeds locally, but the error says storage never saw it
+        let storage = expect_success(get_storage_address_local_rpc(&address), "Failed to get storage address").await?;
+        expect_success(set_storage_attempts_local_rpc(&address, 1), "Failed to set storage attempts").await?;
+        expect_success(
+            set_storage_address_local_rpc(&address, "indexer.os@missing-storage:app:sys".to_string()),
+            "Failed to set storage address"
+        ).await?;
+        let response = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Unstored Task")), "Failed to call create_task").await?;
+        expect_success(set_storage_address_local_rpc(&address, storage), "Failed to restore storage address").await?;
+        expect_success(set_storage_attempts_local_rpc(&address, 3), "Failed to restore storage attempts").await?;
+        if !response.success || response.error != Some(TaskError::StorageUnavailable) {
+            fail!(format!("Create without storage should report StorageUnavailable: {:?}", response.error));
+        }
+        
+        Ok(())
+    },
+
+    test_cursor_pagination: async {
+        let stable: Vec<String> = expect_success(list_task_summaries_http_rpc(TASK_MANAGER_URL), "Failed to list tasks").await?
+            .into_iter()
+            .map(|summary| summary.id)
+            .collect();
+        
+        // Page through two at a time, creating a task after the first page
+        let mut seen: Vec<String> = Vec::new();
+        let mut cursor = None;
+        let mut inserted = false;
+        loop {
+            let page = expect_success(get_tasks_after_http_rpc(TASK_MANAGER_URL, cursor, 2), "Failed to get tasks page").await?;
+            seen.extend(page.tasks.into_iter().map(|task| task.id));
+            if !inserted {
+                expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Mid-Scroll Task")), "Failed to create task").await?;
+                inserted = true;
+            }
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        
+        let mut unique = seen.clone();
+        unique.sort();
+        unique.dedup();
+        if unique.len() != seen.len() {
+            fail!("Cursor paging returned a task twice");
+        }
+        if let Some(missing) = stable.iter().find(|id| !seen.contains(id)) {
+            fail!(format!("Cursor paging skipped task {}", missing));
+        }
+        
+        let page = expect_success(
+            get_tasks_after_http_rpc(TASK_MANAGER_URL, Some("not-a-cursor".to_string()), 2),
+            "Failed to call get_tasks_after"
+        ).await?;
+        if !page.tasks.is_empty() || page.next_cursor.is_some() {
+            fail!("A malformed cursor should yield an empty final page");
+        }
+        
+        Ok(())
+    },
+
+    test_update_statuses_bulk: async {
+        let mut ids = Vec::new();
+        for title in ["Bulk Status A", "Bulk Status B"] {
+            let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task(title)), "Failed to create task").await?.task else {
+                fail!("Created task missing from response");
+            };
+            ids.push(task.id);
+        }
+        let update = |task_id: &str, new_status: TaskStatus| TaskStatusUpdateRequest {
+            task_id: task_id.to_string(),
+            new_status,
+            expected_version: None,
+            correlation_id: None,
+        };
+        
+        // A is moved twice, B can't skip to Completed, and the unknown id is reported on its own
+        let updates = vec![
+            update(&ids[0], TaskStatus::InProgress),
+            update(&ids[1], TaskStatus::Completed),
+            update("no-such-task", TaskStatus::InProgress),
+            update(&ids[0], TaskStatus::Completed),
+        ];
+        let response = expect_success(update_statuses_bulk_http_rpc(TASK_MANAGER_URL, updates), "Failed to update statuses").await?;
+        
+        if response.updated.len() != 1 || response.updated[0].id != ids[0] || response.updated[0].status != TaskStatus::Completed {
+            fail!(format!("Only task A should be updated, to Completed: {:?}", response.updated));
+        }
+        if response.failed.len() != 2 {
+            fail!(format!("Expected two failures: {:?}", response.failed));
+        }
+        if response.failed[0].task_id != ids[1] || !matches!(response.failed[0].error, TaskError::IllegalTransition(_)) {
+            fail!(format!("Task B should fail with an illegal transition: {:?}", response.failed[0]));
+        }
+        if response.failed[1].task_id != "no-such-task" || response.failed[1].error != TaskError::NotFound {
+            fail!(format!("The unknown id should fail with NotFound: {:?}", response.failed[1]));
+        }
+        
+        let Some(b) = expect_success(get_task_http_rpc(TASK_MANAGER_URL, ids[1].clone()), "Failed to get task").await?.task else {
+            fail!("Task B missing");
+        };
+        if b.status != TaskStatus::Pending {
+            fail!("A failed update must leave the task unchanged");
+        }
+        
+        Ok(())
+    },
+
+    test_metrics: async {
+        let metrics = expect_success(metrics_http_rpc(TASK_MANAGER_URL), "Failed to get metrics").await?;
+        if !metrics.ends_with('\n') {
+            fail!("Metrics text must end with a newline");
+        }
+        
+        // Every sample belongs to a family declared by preceding HELP and TYPE lines
+        let mut declared: Vec<String> = Vec::new();
+        for line in metrics.lines() {
+            if let Some(rest) = line.strip_prefix("# HELP ") {
+                let Some((name, _)) = rest.split_once(' ') else {
+                    fail!(format!("HELP line without text: {:?}", line));
+                };
+                declared.push(name.to_string());
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                if !matches!(rest.split_once(' '), Some((name, "gauge" | "counter")) if declared.last().is_some_and(|last| last == name)) {
+                    fail!(format!("TYPE line should follow its HELP line: {:?}", line));
+                }
+                continue;
+            }
+            let Some((series, value)) = line.rsplit_once(' ') else {
+                fail!(format!("Sample without a value: {:?}", line));
+            };
+            let name = series.split('{').next().unwrap_or_default();
+            if !declared.iter().any(|declared| declared == name) || value.parse::<u64>().is_err() {
+                fail!(format!("Malformed sample: {:?}", line));
+            }
+            if series.contains('{') && !(series.ends_with("\"}") && series.matches('"').count() % 2 == 0) {
+                fail!(format!("Malformed labels: {:?}", line));
+            }
+        }
+        
+        for family in ["taskmanager_tasks_total", "taskmanager_requests_total", "taskmanager_ws_connections"] {
+            if !declared.iter().any(|name| name == family) {
+                fail!(format!("Missing metric {}", family));
+            }
+        }
+        if !metrics.contains("taskmanager_tasks_by_status{status=\"pending\"} ") {
+            fail!("Missing the pending task count");
+        }
+        
+        Ok(())
+    },
+
+    test_ws_connection_cap: async {
+        let address = task_manager_address();
+        let (first, second) = (900_001, 900_002);
+        
+        // The test node has no real subscribers, so a cap of one leaves room for one channel
+        test_remote_call(set_max_ws_connections_local_rpc(&address, 1), true, "Failed to set connection cap").await?;
+        let admitted = expect_success(subscribe_test_channel_local_rpc(&address, first), "Failed to subscribe").await?;
+        let refused = expect_success(subscribe_test_channel_local_rpc(&address, second), "Failed to subscribe").await?;
+        let resubscribed = expect_success(subscribe_test_channel_local_rpc(&address, first), "Failed to subscribe").await?;
+        let stats = expect_success(get_statistics_local_rpc(&address), "Failed to get statistics").await?;
+        let health = expect_success(health_http_rpc(TASK_MANAGER_URL), "Failed to get health").await?;
+        test_remote_call(disconnect_ws_channel_local_rpc(&address, first), true, "Failed to disconnect channel").await?;
+        test_remote_call(disconnect_ws_channel_local_rpc(&address, second), false, "A refused channel should not be subscribed").await?;
+        // Back to the default cap
+        test_remote_call(set_max_ws_connections_local_rpc(&address, 1_000), true, "Failed to restore connection cap").await?;
+        
+        if admitted.first().map(String::as_str) != Some("Hello") {
+            fail!(format!("The first channel should be admitted: {:?}", admitted));
+        }
+        if refused != ["ServerBusy"] {
+            fail!(format!("A channel past the cap should get ServerBusy: {:?}", refused));
+        }
+        if resubscribed.first().map(String::as_str) != Some("Hello") {
+            fail!(format!("Re-subscribing an open channel should not count against the cap: {:?}", resubscribed));
+        }
+        if stats.ws_connection_count != 1 || health.ws_connection_count != 1 {
+            fail!(format!("Expected one connection, stats {} health {}", stats.ws_connection_count, health.ws_connection_count));
+        }
+        
+        test_remote_call(set_max_ws_connections_local_rpc(&address, 0), false, "A cap of zero should be rejected").await?;
+        
+        Ok(())
+    },
+
+    test_restore_task: async {
+        let address = task_manager_address();
+        let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Restorable Task")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        
+        let response = expect_success(restore_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to call restore_task").await?;
+        if response.success || response.status_code != 409 {
+            fail!("Restoring a live task should be rejected");
+        }
+        
+        expect_success(cancel_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), None), "Failed to cancel task").await?;
+        expect_success(archive_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to archive task").await?;
+        let response = expect_success(restore_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to restore task").await?;
+        let Some(restored) = response.task.filter(|_| response.success) else {
+            fail!(format!("Restore should succeed: {}", response.message));
+        };
+        if restored.status != TaskStatus::Pending || restored.archived {
+            fail!(format!("A restored task should be pending and unarchived: {:?}", restored));
+        }
+        if !restored.history.iter().any(|event| matches!(event.kind, TaskEventKind::Restored)) {
+            fail!("Restoring should be recorded in the task's history");
+        }
+        
+        let pending = expect_success(get_tasks_by_status_local_rpc(&address, TaskStatus::Pending), "Failed to get pending tasks").await?;
+        if !pending.iter().any(|pending| pending.id == task.id) {
+            fail!("A restored task should be listed as Pending again");
+        }
+        
+        Ok(())
+    },
+
+    test_archived_tasks_hidden: async {
+        let address = task_manager_address();
+        let Some(parent) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Hidden Parent")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        let mut req = new_task("Hidden Task");
+        req.tags = vec!["hidden-archive".to_string()];
+        req.priority = TaskPriority::Low;
+        req.due_at = Some(1);
+        req.parent_id = Some(parent.id.clone());
+        let Some(unassigned) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, req.clone()), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        req.assignees = vec!["hidden-archive-assignee".to_string()];
+        let Some(assigned) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        for task in [&assigned, &unassigned] {
+            expect_success(archive_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to archive task").await?;
+        }
+        
+        let page = expect_success(
+            get_tasks_paginated_http_rpc(TASK_MANAGER_URL, caller_utils::PageRequest { offset: 0, limit: 10_000 }),
+            "Failed to get tasks page"
+        ).await?;
+        if page.total != page.tasks.len() as u64 {
+            fail!(format!("The total should count only listed tasks: {} vs {}", page.total, page.tasks.len()));
+        }
+        let listings = [
+            ("paginated", page.tasks),
+            ("after cursor", expect_success(get_tasks_after_http_rpc(TASK_MANAGER_URL, None, 10_000), "Failed to get tasks page").await?.tasks),
+            ("by tag", expect_success(get_tasks_by_tag_http_rpc(TASK_MANAGER_URL, "hidden-archive".to_string()), "Failed to get tasks by tag").await?),
+            ("overdue", expect_success(get_overdue_tasks_http_rpc(TASK_MANAGER_URL), "Failed to get overdue tasks").await?),
+            ("subtasks", expect_success(get_subtasks_http_rpc(TASK_MANAGER_URL, parent.id.clone()), "Failed to get subtasks").await?),
+            ("by priority", expect_success(get_tasks_by_priority_local_rpc(&address, TaskPriority::Low), "Failed to get tasks by priority").await?),
+            ("by assignee", expect_success(get_tasks_by_assignee_local_rpc(&address, "hidden-archive-assignee".to_string()), "Failed to get tasks by assignee").await?),
+            ("unassigned", expect_success(get_unassigned_tasks_local_rpc(&address), "Failed to get unassigned tasks").await?),
+        ];
+        for (listing, tasks) in listings {
+            if tasks.iter().any(|task| task.id == assigned.id || task.id == unassigned.id) {
+                fail!(format!("Archived tasks should be hidden from the {} listing", listing));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_storage_error_message: async {
+        let address = task_manager_address();
+        let storage = expect_success(get_storage_address_local_rpc(&address), "Failed to get storage address").await?;
+        
+        // Reconciling against a missing storage process reports why, in words rather than a variant name
+        expect_success(set_storage_attempts_local_rpc(&address, 1), "Failed to set storage attempts").await?;
+        expect_success(
+            set_storage_address_local_rpc(&address, "indexer.os@missing-storage:app:sys".to_string()),
+            "Failed to set storage address"
+        ).await?;
+        let report = expect_success(reconcile_with_storage_local_rpc(&address), "Failed to reconcile").await?;
+        expect_success(set_storage_address_local_rpc(&address, storage), "Failed to restore storage address").await?;
+        expect_success(set_storage_attempts_local_rpc(&address, 3), "Failed to restore storage attempts").await?;
+        
+        match report.storage_error.as_deref() {
+            Some("storage process did not reply in time" | "storage process is offline or unreachable") => {}
+            other => {
+                fail!(format!("Expected a readable storage error, got {:?}", other));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_task_dependencies: async {
+        let mut ids = Vec::new();
+        for title in ["Dependency A", "Dependency B", "Dependency C"] {
+            let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task(title)), "Failed to create task").await?.task else {
+                fail!("Created task missing from response");
+            };
+            ids.push(task.id);
+        }
+        let (a, b, c) = (ids[0].clone(), ids[1].clone(), ids[2].clone());
+        let start = |task_id: &str| TaskStatusUpdateRequest {
+            task_id: task_id.to_string(),
+            new_status: TaskStatus::InProgress,
+            expected_version: None,
+            correlation_id: None,
+        };
+        
+        // B waits for A, C waits for B
+        expect_success(add_dependency_http_rpc(TASK_MANAGER_URL, b.clone(), a.clone()), "Failed to add dependency").await?;
+        expect_success(add_dependency_http_rpc(TASK_MANAGER_URL, c.clone(), b.clone()), "Failed to add dependency").await?;
+        
+        // A depending on C would close the loop
+        let response = expect_success(add_dependency_http_rpc(TASK_MANAGER_URL, a.clone(), c.clone()), "Failed to call add_dependency").await?;
+        if response.success || !response.message.contains("cycle") {
+            fail!(format!("A cyclic dependency should be rejected: {}", response.message));
+        }
+        let response = expect_success(add_dependency_http_rpc(TASK_MANAGER_URL, a.clone(), a.clone()), "Failed to call add_dependency").await?;
+        if response.success {
+            fail!("A task depending on itself should be rejected");
+        }
+        
+        let blocked = expect_success(get_blocked_tasks_http_rpc(TASK_MANAGER_URL), "Failed to get blocked tasks").await?;
+        if !blocked.iter().any(|task| task.id == b) || !blocked.iter().any(|task| task.id == c) || blocked.iter().any(|task| task.id == a) {
+            fail!("B and C should be blocked, A not");
+        }
+        
+        let response = expect_success(update_task_status_http_rpc(TASK_MANAGER_URL, start(&b)), "Failed to call update_task_status").await?;
+        match &response.error {
+            Some(TaskError::Blocked(blockers)) if *blockers == [a.clone()] && response.message.contains(&a) => {}
+            other => {
+                fail!(format!("Starting B before A is done should be blocked by A: {:?}", other));
+            }
+        }
+        
+        // Once A is Completed, B can start
+        for status in [TaskStatus::InProgress, TaskStatus::Completed] {
+            let update = TaskStatusUpdateRequest { new_status: status, ..start(&a) };
+            expect_success(update_task_status_http_rpc(TASK_MANAGER_URL, update), "Failed to update A").await?;
+        }
+        let response = expect_success(update_task_status_http_rpc(TASK_MANAGER_URL, start(&b)), "Failed to call update_task_status").await?;
+        if !response.success {
+            fail!(format!("B should start once A is completed: {}", response.message));
+        }
+        
+        // Dropping the dependency unblocks C
+        expect_success(remove_dependency_http_rpc(TASK_MANAGER_URL, c.clone(), b.clone()), "Failed to remove dependency").await?;
+        let blocked = expect_success(get_blocked_tasks_http_rpc(TASK_MANAGER_URL), "Failed to get blocked tasks").await?;
+        if blocked.iter().any(|task| task.id == b || task.id == c) {
+            fail!("Neither B nor C should be blocked any more");
+        }
+        
+        Ok(())
+    },
+
+    test_patch_task: async {
+        let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Patch Me")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        
+        let patch = serde_json::json!({
+            "title": "Patched Title",
+            "status": "InProgress",
+            "assigned_to": "patch-alice",
+            "priority": "High",
+            "tags": ["Patched", " patched "],
+        });
+        let response = expect_success(patch_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), patch, true), "Failed to patch task").await?;
+        let Some(patched) = response.task.filter(|_| response.success) else {
+            fail!(format!("A valid patch should apply: {}", response.message));
+        };
+        if patched.title != "Patched Title"
+            || patched.status != TaskStatus::InProgress
+            || patched.assignees != ["patch-alice"]
+            || patched.priority != TaskPriority::High
+            || patched.tags != ["patched"]
+        {
+            fail!(format!("Patched fields don't match: {:?}", patched));
+        }
+        
+        // An unknown key rejects the whole patch in strict mode and is skipped otherwise
+        let patch = serde_json::json!({"description": "Patched", "colour": "red"});
+        let response = expect_success(patch_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), patch.clone(), true), "Failed to call patch_task").await?;
+        if response.success || response.status_code != 400 || !response.message.contains("colour") {
+            fail!(format!("A strict patch with an unknown key should be rejected: {}", response.message));
+        }
+        let unchanged = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to get task").await?.task;
+        if unchanged.is_none_or(|unchanged| !unchanged.description.is_empty()) {
+            fail!("A rejected patch must not change the task");
+        }
+        let response = expect_success(patch_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), patch, false), "Failed to call patch_task").await?;
+        if !response.success || !response.message.contains("colour") || response.task.is_none_or(|task| task.description != "Patched") {
+            fail!(format!("A lenient patch should apply known keys and name the ignored one: {}", response.message));
+        }
+        
+        let response = expect_success(
+            patch_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), serde_json::json!({"title": "  "}), true),
+            "Failed to call patch_task"
+        ).await?;
+        if response.success || response.status_code != 400 {
+            fail!("A blank title should be rejected");
+        }
+        
+        Ok(())
+    },
+
+    test_recent_activity: async {
+        let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Activity Task")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        let update = TaskStatusUpdateRequest {
+            task_id: task.id.clone(),
+            new_status: TaskStatus::InProgress,
+            expected_version: None,
+            correlation_id: None,
+        };
+        expect_success(update_task_status_http_rpc(TASK_MANAGER_URL, update), "Failed to update task").await?;
+        expect_success(
+            reassign_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), Some("activity-alice".to_string())),
+            "Failed to reassign task"
+        ).await?;
+        
+        let capped = expect_success(get_recent_activity_http_rpc(TASK_MANAGER_URL, 2), "Failed to get activity").await?;
+        if capped.len() != 2 {
+            fail!(format!("Expected the feed to be capped at 2, got {}", capped.len()));
+        }
+        
+        // A limit past the number of events returns all of them, newest first
+        let feed = expect_success(get_recent_activity_http_rpc(TASK_MANAGER_URL, 1_000_000), "Failed to get activity").await?;
+        if feed.len() < 3 || feed.windows(2).any(|pair| pair[0].at < pair[1].at) {
+            fail!("The feed should hold every event, newest first");
+        }
+        let ours: Vec<&TaskEventKind> = feed.iter().filter(|entry| entry.task_id == task.id).map(|entry| &entry.kind).collect();
+        if !matches!(
+            ours.as_slice(),
+            [TaskEventKind::Reassigned(_), TaskEventKind::StatusChanged(_), TaskEventKind::Created]
+        ) {
+            fail!(format!("The task's events should appear newest first: {:?}", ours));
+        }
+        if capped.iter().map(|entry| &entry.kind).ne(feed.iter().take(2).map(|entry| &entry.kind)) {
+            fail!("The capped feed should be the start of the full feed");
+        }
+        
+        Ok(())
+    },
+
+    test_ws_gzip_snapshot: async {
+        use base64::prelude::{Engine, BASE64_STANDARD};
+        use std::io::Read;
+        
+        let address = task_manager_address();
+        let expected: Vec<String> = expect_success(get_all_tasks_http_rpc(TASK_MANAGER_URL), "Failed to get tasks").await?
+            .into_iter()
+            .map(|task| task.id)
+            .collect();
+        
+        // Without compression the chunks arrive as plain JSON
+        let plain = expect_success(get_snapshot_frames_local_rpc(&address, None), "Failed to get snapshot frames").await?;
+        if !plain.first().is_some_and(|frame| frame.starts_with("{\"SnapshotChunk\"")) {
+            fail!(format!("An uncompressed snapshot should start with a plain chunk: {:?}", plain.first()));
+        }
+        
+        let frames = expect_success(
+            get_snapshot_frames_local_rpc(&address, Some("gzip".to_string())),
+            "Failed to get snapshot frames"
+        ).await?;
+        let mut received = Vec::new();
+        for frame in &frames {
+            let event: serde_json::Value = serde_json::from_str(frame)?;
+            if event.get("SnapshotComplete").is_some() {
+                continue;
+            }
+            let Some(envelope) = event.get("CompressedSnapshot") else {
+                fail!(format!("Chunks for a gzip client should be compressed: {}", frame));
+            };
+            if envelope["encoding"] != "gzip" {
+                fail!(format!("Unexpected encoding {}", envelope["encoding"]));
+            }
+            let compressed = BASE64_STANDARD.decode(envelope["data"].as_str().unwrap_or_default())?;
+            let mut json = String::new();
+            flate2::read::GzDecoder::new(compressed.as_slice()).read_to_string(&mut json)?;
+            let chunk: serde_json::Value = serde_json::from_str(&json)?;
+            for task in chunk["SnapshotChunk"]["tasks"].as_array().into_iter().flatten() {
+                received.push(task["id"].as_str().unwrap_or_default().to_string());
+            }
+        }
+        
+        let mut expected_sorted = expected.clone();
+        expected_sorted.sort();
+        received.sort();
+        if received != expected_sorted {
+            fail!(format!("Decompressed snapshot holds {} tasks, expected {}", received.len(), expected.len()));
+        }
+        
+        Ok(())
+    },
+
+    test_auto_archive: async {
+        let address = task_manager_address();
+        
+        // A freshly created task tells us the manager's clock
+        let Some(clock) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Clock Task")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        let completed_task = |id: &str, completed_at: Option<u64>| serde_json::json!({
+            "id": id,
+            "title": "Retention Task",
+            "description": "",
+            "status": "Completed",
+            "created_at": 1,
+            "completed_at": completed_at,
+        });
+        // The last task predates `completed_at` tracking and must not be treated as old
+        let request = serde_json::json!({
+            "ImportTasks": (
+                vec![
+                    completed_task("retention-old", Some(100)),
+                    completed_task("retention-recent", Some(clock.created_at)),
+                    completed_task("retention-untracked", None),
+                ],
+                "RegenerateId",
+            )
+        });
+        let imported = expect_success(
+            caller_utils::send_http_rpc::<caller_utils::BulkCreateResponse>(&request, TASK_MANAGER_URL),
+            "Failed to import tasks"
+        ).await?;
+        let [old, recent, untracked] = &imported.created[..] else {
+            fail!(format!("All three tasks should import, failed: {:?}", imported.failed));
+        };
+        
+        if expect_success(set_completed_retention_secs_local_rpc(&address, Some(0)), "Failed to set retention").await? {
+            fail!("A zero retention should be rejected");
+        }
+        expect_success(set_completed_retention_secs_local_rpc(&address, Some(86_400)), "Failed to set retention").await?;
+        let archived = expect_success(run_auto_archive_local_rpc(&address), "Failed to run auto-archive").await?;
+        expect_success(set_completed_retention_secs_local_rpc(&address, None), "Failed to reset retention").await?;
+        
+        if !archived.contains(&old.id) {
+            fail!(format!("Task completed long ago should be archived: {:?}", archived));
+        }
+        if archived.contains(&recent.id) || archived.contains(&untracked.id) {
+            fail!(format!("Only the old task should be archived: {:?}", archived));
+        }
+        for (task, expected) in [(old, true), (recent, false), (untracked, false)] {
+            let Some(stored) = expect_success(get_task_http_rpc(TASK_MANAGER_URL, task.id.clone()), "Failed to get task").await?.task else {
+                fail!(format!("Task {} missing", task.id));
+            };
+            if stored.archived != expected {
+                fail!(format!("Task {} archived is {}, expected {}", task.id, stored.archived, expected));
+            }
+        }
+        
+        Ok(())
+    },
+
+    test_reassign_all: async {
+        let create = |title: &str, assignees: &[&str]| {
+            let mut req = new_task(title);
+            req.assignees = assignees.iter().map(|assignee| assignee.to_string()).collect();
+            create_task_http_rpc(TASK_MANAGER_URL, req)
+        };
+        let mut leaving = Vec::new();
+        let handovers: [(&str, &[&str]); 2] = [
+            ("Handover A", &["handover-leaver"]),
+            ("Handover B", &["handover-leaver", "handover-stayer"]),
+        ];
+        for (title, assignees) in handovers {
+            let Some(task) = expect_success(create(title, assignees), "Failed to create task").await?.task else {
+                fail!("Created task missing from response");
+            };
+            leaving.push(task.id);
+        }
+        let Some(other) = expect_success(create("Not Handed Over", &["handover-stayer"]), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        
+        let report = expect_success(
+            reassign_all_http_rpc(TASK_MANAGER_URL, "handover-leaver".to_string(), Some("handover-successor".to_string())),
+            "Failed to reassign tasks"
+        ).await?;
+        let mut reassigned: Vec<String> = report.updated.iter().map(|task| task.id.clone()).collect();
+        reassigned.sort();
+        leaving.sort();
+        if reassigned != leaving || !report.failed.is_empty() {
+            fail!(format!("Exactly the leaver's tasks should be reassigned: {:?}", report));
+        }
+        for task in &report.updated {
+            if task.assignees.contains(&"handover-leaver".to_string()) || !task.assignees.contains(&"handover-successor".to_string()) {
+                fail!(format!("Task {} should have moved to the successor: {:?}", task.id, task.assignees));
+            }
+        }
+        if !report.updated.iter().any(|task| task.assignees == ["handover-successor", "handover-stayer"]) {
+            fail!("Other assignees should be kept");
+        }
+        let Some(untouched) = expect_success(get_task_http_rpc(TASK_MANAGER_URL, other.id.clone()), "Failed to get task").await?.task else {
+            fail!("Non-matching task missing");
+        };
+        if untouched.assignees != ["handover-stayer"] || untouched.version != other.version {
+            fail!(format!("Non-matching task should be left alone: {:?}", untouched.assignees));
+        }
+        
+        // Unassigning drops the successor and keeps everyone else
+        let cleared = expect_success(reassign_all_http_rpc(TASK_MANAGER_URL, "handover-successor".to_string(), None), "Failed to unassign tasks").await?;
+        if cleared.updated.len() != 2 || cleared.updated.iter().any(|task| task.assignees.contains(&"handover-successor".to_string())) {
+            fail!(format!("The successor should be unassigned from both tasks: {:?}", cleared.updated));
+        }
+        
+        let empty = expect_success(reassign_all_http_rpc(TASK_MANAGER_URL, "nobody-at-all".to_string(), None), "Failed to reassign tasks").await?;
+        if !empty.updated.is_empty() || !empty.failed.is_empty() {
+            fail!(format!("Reassigning from someone with no tasks should change nothing: {:?}", empty));
+        }
+        
+        Ok(())
+    },
+
+    test_assignee_format: async {
+        let address = task_manager_address();
+        let invalid = "not an address".to_string();
+        let valid = "fake.os@notifier:task-manager:uncentered.os".to_string();
+        let create = |assignee: &String| {
+            let mut req = new_task("Assignee Format Task");
+            req.assignees = vec![assignee.clone()];
+            create_task_http_rpc(TASK_MANAGER_URL, req)
+        };
+        
+        // Free-form, the default, takes any assignee
+        let freeform = expect_success(create(&invalid), "Failed to create task").await?;
+        let Some(task) = freeform.task.clone() else {
+            fail!(format!("A free-form assignee should be accepted: {}", freeform.message));
+        };
+        
+        // Collect the address-mode results before switching back, so a failure can't leave
+        // the policy set for later tests
+        expect_success(set_assignee_format_local_rpc(&address, AssigneeFormat::Address), "Failed to set assignee format").await?;
+        let created_invalid = expect_success(create(&invalid), "Failed to create task").await?;
+        let created_valid = expect_success(create(&valid), "Failed to create task").await?;
+        let reassigned_invalid = expect_success(
+            reassign_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), Some(invalid.clone())),
+            "Failed to reassign task"
+        ).await?;
+        let updated_invalid = expect_success(
+            update_task_http_rpc(TASK_MANAGER_URL, TaskUpdateRequest {
+                task_id: task.id.clone(),
+                title: None,
+                description: None,
+                assignees: Some(vec![valid.clone(), invalid.clone()]),
+                new_status: None,
+                parent_id: None,
+                expected_version: None,
+                correlation_id: None,
+            }),
+            "Failed to update task"
+        ).await?;
+        let reassigned_valid = expect_success(
+            reassign_task_http_rpc(TASK_MANAGER_URL, task.id.clone(), Some(valid.clone())),
+            "Failed to reassign task"
+        ).await?;
+        expect_success(set_assignee_format_local_rpc(&address, AssigneeFormat::Freeform), "Failed to reset assignee format").await?;
+        
+        for (what, response) in [("create", &created_invalid), ("reassign", &reassigned_invalid), ("update", &updated_invalid)] {
+            if response.success || response.status_code != 400 || !response.message.contains("not a valid address") {
+                fail!(format!("An invalid assignee should be rejected on {}: {:?}", what, response));
+            }
+        }
+        if !created_valid.success || !reassigned_valid.success {
+            fail!(format!("A valid address should be accepted: {} / {}", created_valid.message, reassigned_valid.message));
+        }
+        if reassigned_valid.task.map(|t| t.assignees) != Some(vec![valid.clone()]) {
+            fail!("A valid reassignment should replace the assignees");
+        }
+        
+        Ok(())
+    },
+
+    test_compact_storage_encoding: async {
+        let address = task_manager_address();
+        let mut req = new_task("Packed Task");
+        req.description = "Carries enough fields to compare the encodings".to_string();
+        req.tags = vec!["storage".to_string(), "encoding".to_string()];
+        req.assignees = vec!["packer".to_string()];
+        expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?;
+        
+        let report = expect_success(compare_storage_encodings_local_rpc(&address), "Failed to compare encodings").await?;
+        if !report.round_trip_equal {
+            fail!("Both encodings should decode back to the same tasks");
+        }
+        if report.task_count == 0 || report.packed_bytes >= report.json_bytes {
+            fail!(format!("Packed tasks should be smaller than JSON: {:?}", report));
+        }
+        // Packing drops the field names and quoting, while base64 adds back a third; log the
+        // net reduction so it can be tracked as the task fields change
+        let reduction = 100 - report.packed_bytes * 100 / report.json_bytes;
+        print_to_terminal(0, &format!(
+            "Storing {} tasks: {} bytes as JSON, {} bytes packed ({}% smaller)",
+            report.task_count, report.json_bytes, report.packed_bytes, reduction
+        ));
+        
+        // A storage process that can't be asked about packed tasks keeps getting JSON
+        let storage = expect_success(get_storage_address_local_rpc(&address), "Failed to get storage address").await?;
+        expect_success(
+            set_storage_address_local_rpc(&address, "indexer.os@missing-storage:app:sys".to_string()),
+            "Failed to set storage address"
+        ).await?;
+        let packed = expect_success(set_compact_storage_local_rpc(&address, true), "Failed to enable compact storage").await?;
+        expect_success(set_compact_storage_local_rpc(&address, false), "Failed to disable compact storage").await?;
+        expect_success(set_storage_address_local_rpc(&address, storage), "Failed to restore storage address").await?;
+        if packed {
+            fail!("Compact storage should fall back to JSON when storage doesn't support it");
+        }
+        
+        Ok(())
+    },
+
+    test_search_comments: async {
+        let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Quiet Task")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        let body = "Blocked on the ZEPHYRINE rollout".to_string();
+        expect_success(
+            add_comment_http_rpc(TASK_MANAGER_URL, task.id.clone(), "alice".to_string(), body),
+            "Failed to add comment"
+        ).await?;
+        let search = |query: &str, include_comments: bool| {
+            search_tasks_http_rpc(TASK_MANAGER_URL, query.to_string(), include_comments)
+        };
+        
+        let without = expect_success(search("zephyrine", false), "Failed to search tasks").await?;
+        if without.iter().any(|result| result.task.id == task.id) {
+            fail!("Comments should only be searched when asked to");
+        }
+        
+        let with = expect_success(search("Zephyrine", true), "Failed to search tasks").await?;
+        let Some(hit) = with.iter().find(|result| result.task.id == task.id) else {
+            fail!("A term only in a comment should find the task, case-insensitively");
+        };
+        if hit.matched_in != MatchField::Comment {
+            fail!(format!("The match should be reported in the comment, got {:?}", hit.matched_in));
+        }
+        
+        let by_title = expect_success(search("quiet task", true), "Failed to search tasks").await?;
+        if !by_title.iter().any(|result| result.task.id == task.id && result.matched_in == MatchField::Title) {
+            fail!("A title match should be reported as such");
+        }
+        
+        Ok(())
+    },
+
+    test_task_ownership: async {
+        let address = task_manager_address();
+        let tester = hyperware_process_lib::our().to_string();
+        let start = |task_id: &str| TaskStatusUpdateRequest {
+            task_id: task_id.to_string(),
+            new_status: TaskStatus::InProgress,
+            expected_version: None,
+            correlation_id: None,
+        };
+        
+        // HTTP callers are anonymous, so a task created over HTTP has no owner and is open to all
+        let Some(open) = expect_success(
+            create_task_http_rpc(TASK_MANAGER_URL, new_task("Open Task")),
+            "Failed to create task"
+        ).await?.task else {
+            fail!("Created task missing from response");
+        };
+        if open.owner.is_some() {
+            fail!(format!("Task created over HTTP should have no owner, got {:?}", open.owner));
+        }
+        let started = expect_success(
+            update_task_status_http_rpc(TASK_MANAGER_URL, start(&open.id)),
+            "Failed to update task status"
+        ).await?;
+        if !started.success {
+            fail!(format!("Anyone should be able to update an unowned task: {}", started.message));
+        }
+        
+        // This test process creates a task of its own, which anonymous HTTP callers may not change
+        let Some(own) = expect_success(
+            create_task_local_rpc(&address, new_task("Owned Task")),
+            "Failed to create task"
+        ).await?.task else {
+            fail!("Created task missing from response");
+        };
+        if own.owner.as_deref() != Some(tester.as_str()) {
+            fail!(format!("A task created by a process should be owned by it, got {:?}", own.owner));
+        }
+        let denied_update = expect_success(
+            update_task_status_http_rpc(TASK_MANAGER_URL, start(&own.id)),
+            "Failed to update task status"
+        ).await?;
+        let denied_archive = expect_success(
+            archive_task_http_rpc(TASK_MANAGER_URL, own.id.clone()),
+            "Failed to archive task"
+        ).await?;
+        let denied_delete = expect_success(
+            delete_task_http_rpc(TASK_MANAGER_URL, own.id.clone()),
+            "Failed to delete task"
+        ).await?;
+        for (what, response) in [("update", &denied_update), ("archive", &denied_archive), ("delete", &denied_delete)] {
+            if response.success || response.status_code != 403 || response.error != Some(TaskError::Forbidden) {
+                fail!(format!("An anonymous {} of an owned task should be forbidden: {:?}", what, response));
+            }
+        }
+        let reread = expect_success(get_task_http_rpc(TASK_MANAGER_URL, own.id.clone()), "Failed to get task").await?;
+        if reread.task.map(|t| (t.status, t.archived)) != Some((TaskStatus::Pending, false)) {
+            fail!("Forbidden changes should leave the task alone");
+        }
+        
+        // The owner may change it, acting as itself through a local request
+        let owner_update = expect_success(
+            update_task_status_local_rpc(&address, start(&own.id)),
+            "Failed to update task status"
+        ).await?;
+        if !owner_update.success {
+            fail!(format!("The owner should be able to update the task: {}", owner_update.message));
+        }
+        
+        // Every other change is gated the same way
+        let denied_cancel = expect_success(
+            cancel_task_http_rpc(TASK_MANAGER_URL, own.id.clone(), None),
+            "Failed to cancel task"
+        ).await?;
+        let denied_tag = expect_success(
+            add_tag_http_rpc(TASK_MANAGER_URL, own.id.clone(), "mine".to_string()),
+            "Failed to add tag"
+        ).await?;
+        let denied_work = expect_success(
+            log_work_http_rpc(TASK_MANAGER_URL, own.id.clone(), 60),
+            "Failed to log work"
+        ).await?;
+        for (what, response) in [("cancel", &denied_cancel), ("tag", &denied_tag), ("work log", &denied_work)] {
+            if response.success || response.error != Some(TaskError::Forbidden) {
+                fail!(format!("An anonymous {} of an owned task should be forbidden: {:?}", what, response));
+            }
+        }
+        
+        // Once its owner completes it, clearing completed tasks anonymously leaves it alone
+        let complete = TaskStatusUpdateRequest { new_status: TaskStatus::Completed, ..start(&own.id) };
+        let completed = expect_success(
+            update_task_status_local_rpc(&address, complete),
+            "Failed to update task status"
+        ).await?;
+        if !completed.success {
+            fail!(format!("The owner should be able to complete the task: {}", completed.message));
+        }
+        let cleared = expect_success(
+            clear_completed_http_rpc(TASK_MANAGER_URL),
+            "Failed to clear completed tasks"
+        ).await?;
+        if cleared.deleted.contains(&own.id) || !cleared.failed.iter().any(|reason| reason.starts_with(&own.id)) {
+            fail!(format!("An anonymous clear should keep and report the owned task: {:?}", cleared));
+        }
+        
+        // A task owned by another process is off limits until this process is made an admin
+        let mut foreign = own.clone();
+        foreign.owner = Some("other.os@task-manager:task-manager:uncentered.os".to_string());
+        let imported = expect_success(
+            import_tasks_http_rpc(TASK_MANAGER_URL, vec![foreign], ImportConflict::RegenerateId),
+            "Failed to import task"
+        ).await?;
+        let Some(foreign) = imported.created.into_iter().next() else {
+            fail!(format!("Importing a foreign task should succeed: {:?}", imported.failed));
+        };
+        let denied = expect_success(
+            update_task_status_local_rpc(&address, start(&foreign.id)),
+            "Failed to update task status"
+        ).await?;
+        if denied.success || denied.error != Some(TaskError::Forbidden) {
+            fail!(format!("A non-owner update should be forbidden: {:?}", denied));
+        }
+        expect_success(set_admins_local_rpc(&address, vec![tester.clone()]), "Failed to set admins").await?;
+        let admin_update = expect_success(
+            update_task_status_local_rpc(&address, start(&foreign.id)),
+            "Failed to update task status"
+        ).await?;
+        expect_success(set_admins_local_rpc(&address, Vec::new()), "Failed to reset admins").await?;
+        if !admin_update.success {
+            fail!(format!("An admin should be able to update any task: {}", admin_update.message));
+        }
+        
+        Ok(())
+    },
+
+    test_malformed_json_body: async {
+        // Truncated JSON, and JSON that isn't a task request, both get the structured error
+        for body in ["{\"title\": \"Half a", "{\"description\": \"No title\"}"] {
+            let reply = expect_success(
+                create_task_from_json_http_rpc(TASK_MANAGER_URL, body.to_string()),
+                "Failed to send malformed body"
+            ).await?;
+            if reply["success"] != false || reply["error"] != "bad_request" {
+                fail!(format!("Malformed body {:?} should get a bad_request error: {}", body, reply));
+            }
+            if !reply["message"].as_str().is_some_and(|message| message.starts_with("Invalid task request")) {
+                fail!(format!("The error should explain what was wrong: {}", reply));
+            }
+        }
+        
+        // A raw body that isn't a request at all never reaches the handler: the framework
+        // rejects it with its own plain-text error, not the structured one
+        let url = url::Url::parse(&format!("{}/api/tasks", TASK_MANAGER_URL))?;
+        let raw = hyperware_process_lib::http::client::send_request_await_response(
+            hyperware_process_lib::http::Method::POST,
+            url,
+            None,
+            5,
+            b"{\"CreateTaskFromJson\": ".to_vec(),
+        )?;
+        if !raw.status().is_client_error() || serde_json::from_slice::<serde_json::Value>(raw.body()).is_ok() {
+            fail!(format!(
+                "A malformed request should get the framework's plain-text error, got {}: {}",
+                raw.status(),
+                String::from_utf8_lossy(raw.body())
+            ));
+        }
+        
+        let body = serde_json::to_string(&new_task("Parsed Task"))?;
+        let created = expect_success(create_task_from_json_http_rpc(TASK_MANAGER_URL, body), "Failed to create task").await?;
+        if created["success"] != true || created["task"]["title"] != "Parsed Task" {
+            fail!(format!("A well-formed body should create the task: {}", created));
+        }
+        
+        Ok(())
+    },
+
+    test_effort_rollup: async {
+        let address = task_manager_address();
+        let before = expect_success(get_effort_rollup_local_rpc(&address), "Failed to get effort rollup").await?;
+        
+        let mut req = new_task("Estimated Task");
+        req.estimate_secs = Some(3_600);
+        let Some(estimated) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, req), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        let Some(unestimated) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task("Unestimated Task")), "Failed to create task").await?.task else {
+            fail!("Created task missing from response");
+        };
+        
+        for secs in [1_000, 600] {
+            expect_success(log_work_http_rpc(TASK_MANAGER_URL, estimated.id.clone(), secs), "Failed to log work").await?;
+        }
+        let logged = expect_success(log_work_http_rpc(TASK_MANAGER_URL, unestimated.id.clone(), 500), "Failed to log work").await?;
+        let Some(task) = logged.task.filter(|_| logged.success) else {
+            fail!(format!("Logging work should succeed: {}", logged.message));
+        };
+        if task.logged_secs != 500 || !task.history.iter().any(|event| matches!(event.kind, TaskEventKind::WorkLogged(_))) {
+            fail!(format!("Logged work should add up and be recorded in history: {:?}", task));
+        }
+        
+        // Nothing, or enough to overflow the total, is rejected without changing the task
+        for secs in [0, u64::MAX] {
+            let rejected = expect_success(log_work_http_rpc(TASK_MANAGER_URL, estimated.id.clone(), secs), "Failed to log work").await?;
+            if rejected.success || rejected.status_code != 400 {
+                fail!(format!("Logging {}s should be rejected: {}", secs, rejected.message));
+            }
+        }
+        let reread = expect_success(get_task_http_rpc(TASK_MANAGER_URL, estimated.id.clone()), "Failed to get task").await?;
+        if reread.task.map(|t| t.logged_secs) != Some(1_600) {
+            fail!("Rejected work should not be logged");
+        }
+        
+        // The estimated task has 2000s left; the unestimated one adds logged time only
+        let after = expect_success(get_effort_rollup_local_rpc(&address), "Failed to get effort rollup").await?;
+        let deltas = (
+            after.task_count - before.task_count,
+            after.unestimated_tasks - before.unestimated_tasks,
+            after.estimated_secs - before.estimated_secs,
+            after.logged_secs - before.logged_secs,
+            after.remaining_secs - before.remaining_secs,
+        );
+        if deltas != (2, 1, 3_600, 2_100, 2_000) {
+            fail!(format!("Unexpected rollup change {:?}: {:?} -> {:?}", deltas, before, after));
+        }
+        
+        Ok(())
+    },
+
+    test_task_board: async {
+        let set_status = |task_id: &str, new_status: TaskStatus| update_task_status_http_rpc(TASK_MANAGER_URL, TaskStatusUpdateRequest {
+            task_id: task_id.to_string(),
+            new_status,
+            expected_version: None,
+            correlation_id: None,
+        });
+        let mut ids = Vec::new();
+        for title in ["Board Pending", "Board Started", "Board Done", "Board Dropped", "Board Archived", "Board Top"] {
+            let Some(task) = expect_success(create_task_http_rpc(TASK_MANAGER_URL, new_task(title)), "Failed to create task").await?.task else {
+                fail!("Created task missing from response");
+            };
+            ids.push(task.id);
+        }
+        let [pending, started, done, dropped, archived, top] = &ids[..] else {
+            fail!("Expected six tasks");
+        };
+        for (id, statuses) in [
+            (started, &[TaskStatus::InProgress][..]),
+            (done, &[TaskStatus::InProgress, TaskStatus::Completed][..]),
+            (archived, &[TaskStatus::InProgress, TaskStatus::Completed][..]),
+        ] {
+            for status in statuses {
+                expect_success(set_status(id, status.clone()), "Failed to update task status").await?;
+            }
+        }
+        expect_success(cancel_task_http_rpc(TASK_MANAGER_URL, dropped.clone(), None), "Failed to cancel task").await?;
+        expect_success(archive_task_http_rpc(TASK_MANAGER_URL, archived.clone()), "Failed to archive task").await?;
+        expect_success(move_task_to_top_http_rpc(TASK_MANAGER_URL, top.clone()), "Failed to move task").await?;
+        
+        let board = expect_success(get_board_http_rpc(TASK_MANAGER_URL), "Failed to get board").await?;
+        let column_of = |id: &String| {
+            [
+                ("pending", &board.pending),
+                ("in_progress", &board.in_progress),
+                ("completed", &board.completed),
+                ("cancelled", &board.cancelled),
+            ]
+            .into_iter()
+            .filter(|(_, column)| column.iter().any(|task| &task.id == id))
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>()
+        };
+        let expected = [
+            (pending, vec!["pending"]),
+            (top, vec!["pending"]),
+            (started, vec!["in_progress"]),
+            (done, vec!["completed"]),
+            (dropped, vec!["cancelled"]),
+            (archived, Vec::new()),
+        ];
+        for (id, columns) in expected {
+            if column_of(id) != columns {
+                fail!(format!("Task {} should be in {:?}, found in {:?}", id, columns, column_of(id)));
+            }
+        }
+        
+        // Columns follow the manual order, and the same board comes back on a second call
+        if board.pending.first().map(|task| &task.id) != Some(top) {
+            fail!("The task moved to the top should head the pending column");
+        }
+        for column in [&board.pending, &board.in_progress, &board.completed, &board.cancelled] {
+            if column.windows(2).any(|pair| pair[0].sort_order > pair[1].sort_order) {
+                fail!("Each column should be sorted by manual order");
+            }
+        }
+        let again = expect_success(get_board_http_rpc(TASK_MANAGER_URL), "Failed to get board").await?;
+        if again.pending.iter().map(|task| &task.id).ne(board.pending.iter().map(|task| &task.id))
+            || again.completed.iter().map(|task| &task.id).ne(board.completed.iter().map(|task| &task.id))
+        {
+            fail!("The board's order should be stable between calls");
+        }
+        
+        Ok(())
+    },
+
+    test_ws_event_sequence: async {
+        let address = task_manager_address();
+        
+        // The greeting carries the run id and current sequence number; the seq is on the frame too
+        let hello_of = |frames: &[String]| -> Option<(String, u64)> {
+            let frame: serde_json::Value = serde_json::from_str(frames.first()?).ok()?;
+            let seq = frame["seq"].as_u64()?;
+            let hello = &frame["event"]["Hello"];
+            let run_id = hello["run_id"].as_str()?.to_string();
+            (hello["seq"].as_u64() == Some(seq)).then_some((run_id, seq))
+        };
+        let frames = expect_success(
+            get_resume_frames_local_rpc(&address, None, None),
+            "Failed to get resume frames"
+        ).await?;
+        let Some((run_id, start)) = hello_of(&frames) else {
+            fail!(format!("Subscribers should be greeted with the run id and seq: {:?}", frames.first()));
+        };
+        
+        let mut created = Vec::new();
+        for title in ["Sequenced 1", "Sequenced 2", "Sequenced 3"] {
+            let response = expect_success(
+                create_task_http_rpc(TASK_MANAGER_URL, new_task(title)),
+                "Failed to create task"
+            ).await?;
+            created.push(response.task.map(|task| task.id).unwrap_or_default());
+        }
+        
+        // Resuming from the first greeting replays each broadcast since, numbered one after another
+        let frames = expect_success(
+            get_resume_frames_local_rpc(&address, Some(run_id.clone()), Some(start)),
+            "Failed to get resume frames"
+        ).await?;
+        let Some((_, latest)) = hello_of(&frames) else {
+            fail!(format!("Resuming subscribers should be greeted with the current seq: {:?}", frames.first()));
+        };
+        let mut seqs = Vec::new();
+        let mut replayed = Vec::new();
+        for frame in frames.iter().skip(1) {
+            let frame: serde_json::Value = serde_json::from_str(frame)?;
+            seqs.push(frame["seq"].as_u64().unwrap_or_default());
+            if let Some(id) = frame["event"]["TaskUpserted"]["id"].as_str() {
+                replayed.push(id.to_string());
+            }
+        }
+        if seqs.first() != Some(&(start + 1)) || seqs.last() != Some(&latest) {
+            fail!(format!("Replay should cover {} to {}, got {:?}", start + 1, latest, seqs));
+        }
+        if seqs.windows(2).any(|pair| pair[1] != pair[0] + 1) {
+            fail!(format!("Broadcast seqs should increase by one each: {:?}", seqs));
+        }
+        if !created.iter().all(|id| replayed.contains(id)) {
+            fail!(format!("Every created task should be replayed: {:?} of {:?}", replayed, created));
+        }
+        
+        // A seq from another run gets a fresh snapshot, even one this run has already passed, as
+        // does a seq this run hasn't reached
+        for (label, resume_run_id, since_seq) in [
+            ("another run", Some("previous-run".to_string()), start),
+            ("no run id", None, start),
+            ("a future seq", Some(run_id.clone()), latest + 1_000),
+        ] {
+            let frames = expect_success(
+                get_resume_frames_local_rpc(&address, resume_run_id, Some(since_seq)),
+                "Failed to get resume frames"
+            ).await?;
+            if !frames.last().is_some_and(|frame| frame.contains("\"SnapshotComplete\"")) {
+                fail!(format!("Resuming from {} should get a snapshot: {:?}", label, frames.last()));
+            }
+        }
+        
+        Ok(())
+    },
 );
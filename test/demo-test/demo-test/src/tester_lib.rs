@@ -194,4 +194,25 @@ where
             });
         }
     }
-}
\ No newline at end of file
+}
+
+/// Helper function to unwrap a successful RPC result whose value can't be compared up front
+///
+/// Fails the test with `error_msg` and the failure kind if the call didn't succeed.
+pub async fn expect_success<T, F>(call_future: F, error_msg: &str) -> anyhow::Result<T>
+where
+    F: std::future::Future<Output = SendResult<T>>,
+{
+    match call_future.await {
+        SendResult::Success(actual) => Ok(actual),
+        SendResult::Timeout => {
+            fail!(format!("{}: timeout", error_msg));
+        }
+        SendResult::Offline => {
+            fail!(format!("{}: offline", error_msg));
+        }
+        SendResult::DeserializationError(e) => {
+            fail!(format!("{}: deserialization error: {}", error_msg, e));
+        }
+    }
+}